@@ -1,7 +1,11 @@
 use byteorder::ByteOrder;
 use core::{marker::PhantomData, mem::size_of};
 
-use crate::XRefFinder;
+use crate::{
+    read::{fits, read_addr, read_disp},
+    target_range::TargetRange,
+    XRefFinder,
+};
 
 /// Verifies that an absolute offset interpretation of `value` would lead the processor to `target`
 pub const fn is_absolute_match(value: usize, target: usize) -> bool {
@@ -9,20 +13,98 @@ pub const fn is_absolute_match(value: usize, target: usize) -> bool {
     value == target
 }
 
-#[cfg(target_pointer_width = "64")]
-pub(crate) fn does_match_absolute<Endian: ByteOrder>(bytes: &[u8], offset: usize, target: usize) -> bool {
-    let value = Endian::read_u64(&bytes[offset..offset + size_of::<u64>()]);
-    is_absolute_match(value as usize, target)
+pub(crate) fn does_match_absolute<Endian: ByteOrder>(
+    bytes: &[u8],
+    offset: usize,
+    target: usize,
+) -> bool {
+    let value = read_addr::<Endian>(bytes, offset, size_of::<usize>());
+    is_absolute_match(value, target)
+}
+
+/// Verifies that following a chain of `hops` absolute pointers, starting at `start_offset`, leads to `target`
+///
+/// Each pointer is converted back into an offset into `bytes` by subtracting `image_base`.
+/// With 0 hops, the address of `start_offset` itself has to be `target`.
+/// Returns false if a pointer leaves `bytes`.
+pub fn follow_chain<Endian: ByteOrder>(
+    bytes: &[u8],
+    start_offset: usize,
+    image_base: usize,
+    hops: usize,
+    target: usize,
+) -> bool {
+    let mut address = image_base.checked_add(start_offset);
+    for _ in 0..hops {
+        address = address
+            .and_then(|address| address.checked_sub(image_base))
+            .filter(|&offset| {
+                offset
+                    .checked_add(size_of::<usize>())
+                    .is_some_and(|end| end <= bytes.len())
+            })
+            .map(|offset| read_addr::<Endian>(bytes, offset, size_of::<usize>()));
+    }
+    address.is_some_and(|address| is_absolute_match(address, target))
 }
 
-#[cfg(target_pointer_width = "32")]
-pub(crate) fn does_match_absolute<Endian: ByteOrder>(bytes: &[u8], offset: usize, target: usize) -> bool {
-    let value = Endian::read_u32(&bytes[offset..offset + size_of::<u32>()]);
-    is_absolute_match(value as usize, target)
+/// The width of the absolute address, which an `AbsoluteFinder` reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointerWidth {
+    /// `u16`, e.g. near pointers in real mode
+    Ptr16,
+    /// A real mode far pointer: a `u16` offset followed by a `u16` segment, which address `(segment << 4) + offset`
+    Far16,
+    /// `u32`, e.g. pointer tables in the small code model or `mov eax, imm32`
+    Ptr32,
+    /// `u64`
+    #[cfg(target_pointer_width = "64")]
+    Ptr64,
 }
 
+impl PointerWidth {
+    /// The amount of bytes the absolute address occupies
+    pub const fn bytes(self) -> usize {
+        match self {
+            Self::Ptr16 => size_of::<u16>(),
+            Self::Far16 => 2 * size_of::<u16>(),
+            Self::Ptr32 => size_of::<u32>(),
+            #[cfg(target_pointer_width = "64")]
+            Self::Ptr64 => size_of::<u64>(),
+        }
+    }
+
+    /// The highest address, which can be referenced
+    const fn max_address(self) -> usize {
+        match self {
+            Self::Far16 => (0xFFFF << 4) + 0xFFFF,
+            _ => (u64::MAX >> (64 - self.bytes() * 8)) as usize,
+        }
+    }
+}
+
+impl Default for PointerWidth {
+    /// The width of `usize`
+    fn default() -> Self {
+        #[cfg(target_pointer_width = "64")]
+        return Self::Ptr64;
+        #[cfg(not(target_pointer_width = "64"))]
+        return Self::Ptr32;
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
 pub struct AbsoluteFinder<Endian: ByteOrder> {
-    target: usize,
+    targets: TargetRange,
+    /// The bytes of the target, as they appear in memory, if there is only a single one
+    repr: Option<[u8; size_of::<usize>()]>,
+    pointer_width: usize,
+    /// Whether the pointer is a `PointerWidth::Far16`
+    far: bool,
+    /// Whether 4 byte values are additionally sign-extended and matched
+    sign_extended: bool,
+    base_address: usize,
+    alignment: usize,
     endian: PhantomData<Endian>,
 }
 
@@ -33,18 +115,218 @@ impl<Endian: ByteOrder> AbsoluteFinder<Endian> {
     ///
     /// * `target`: The address, which the reference should point to
     pub fn new(target: usize) -> Self {
+        Self::with_targets(TargetRange::exact(target))
+    }
+
+    /// Creates a new AbsoluteFinder, that can then find absolute cross references to any address in `lo..hi`
+    ///
+    /// This finds e.g. references to fields of a struct. An empty range (`lo >= hi`) never matches.
+    pub fn new_range(lo: usize, hi: usize) -> Self {
+        Self::with_targets(TargetRange::new(lo, hi))
+    }
+
+    fn with_targets(targets: TargetRange) -> Self {
+        let pointer_width = PointerWidth::default().bytes();
         Self {
-            target,
+            targets,
+            repr: Self::repr(targets, pointer_width),
+            pointer_width,
+            far: false,
+            sign_extended: false,
+            base_address: 0,
+            alignment: 1,
             endian: PhantomData,
         }
     }
+
+    fn repr(targets: TargetRange, pointer_width: usize) -> Option<[u8; size_of::<usize>()]> {
+        targets.single().map(|target| {
+            let mut repr = [0u8; size_of::<usize>()];
+            Endian::write_uint(&mut repr, target as u64, pointer_width);
+            repr
+        })
+    }
+
+    /// Sets the width of the absolute address, which is read and zero-extended. Defaults to the width of `usize`.
+    ///
+    /// Targets above the highest address of the width, e.g. `u32::MAX` for `PointerWidth::Ptr32`, can't be referenced and never match.
+    /// A `PointerWidth::Far16` is converted into its linear address before it is compared.
+    pub fn with_pointer_width(mut self, pointer_width: PointerWidth) -> Self {
+        self.pointer_width = pointer_width.bytes();
+        self.far = pointer_width == PointerWidth::Far16;
+        self.targets = self.targets.capped(pointer_width.max_address());
+        // Many far pointers address the same linear address, so they don't share a first byte
+        self.repr = if self.far {
+            None
+        } else {
+            Self::repr(self.targets, self.pointer_width)
+        };
+        self
+    }
+
+    /// Reads the address of the pointer at `offset`
+    ///
+    /// The caller has to make sure that the pointer is within `bytes`
+    fn read_pointer(&self, bytes: &[u8], offset: usize) -> usize {
+        if self.far {
+            let pointer_offset = read_addr::<Endian>(bytes, offset, size_of::<u16>());
+            let segment = read_addr::<Endian>(bytes, offset + size_of::<u16>(), size_of::<u16>());
+            (segment << 4) + pointer_offset
+        } else {
+            read_addr::<Endian>(bytes, offset, self.pointer_width)
+        }
+    }
+
+    /// Additionally matches 4 byte values, which equal a target once they are sign-extended
+    ///
+    /// Compilers load high addresses, like `0xFFFFFFFF80001000` in the kernel code model, with `mov rax, imm32`,
+    /// whose immediate is sign-extended to 64 bits. The full width values are still matched.
+    pub fn with_sign_extended_imm32(mut self) -> Self {
+        self.sign_extended = true;
+        self
+    }
+
+    fn matches_sign_extended(&self, bytes: &[u8], offset: usize) -> bool {
+        self.sign_extended
+            && fits(bytes, offset, size_of::<i32>())
+            && self
+                .targets
+                .contains(read_disp::<Endian>(bytes, offset, size_of::<i32>()) as usize)
+    }
+
+    /// The bytes, which a reference to the single target can start with
+    fn first_bytes(&self) -> Option<[u8; 2]> {
+        let repr = self.repr?;
+        let mut imm32 = [0u8; size_of::<u32>()];
+        if self.sign_extended {
+            let target = self.targets.lowest();
+            Endian::write_u32(&mut imm32, target as u32);
+        } else {
+            imm32[0] = repr[0];
+        }
+        Some([repr[0], imm32[0]])
+    }
+
+    /// Only accepts references, whose address (`base_address + offset`) is a multiple of `alignment`
+    ///
+    /// Compilers usually align pointers in data sections, so this filters out matches, which start in the middle of a pointer.
+    /// Defaults to an alignment of 1, which accepts every offset.
+    ///
+    /// Arguments:
+    ///
+    /// * `base_address`: The address, at which the scanned bytes are located
+    /// * `alignment`: The alignment, which the address of a reference must have
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alignment` is 0
+    pub fn with_alignment(mut self, base_address: usize, alignment: usize) -> Self {
+        assert!(alignment != 0, "alignment must not be 0");
+        self.base_address = base_address;
+        self.alignment = alignment;
+        self
+    }
+
+    fn is_aligned(&self, offset: usize) -> bool {
+        self.base_address
+            .wrapping_add(offset)
+            .is_multiple_of(self.alignment)
+    }
+
+    /// Finds all references and yields their addresses (`base_address + offset`) instead of their offsets
+    ///
+    /// `base_address` is the address, at which `bytes` is located.
+    /// References, whose address would leave the address space, are skipped.
+    pub fn all_addresses<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        base_address: usize,
+    ) -> impl Iterator<Item = usize> + 'a {
+        self.all(bytes)
+            .filter_map(move |offset| base_address.checked_add(offset))
+    }
+
+    /// Yields every offset starting at `from`, at which a reference could start
+    ///
+    /// With a single target, these are the aligned offsets, which start with the first byte of the target.
+    /// Otherwise every aligned offset is a candidate.
+    pub(crate) fn candidates<'a>(
+        &self,
+        bytes: &'a [u8],
+        from: usize,
+    ) -> impl Iterator<Item = usize> + 'a {
+        let (base_address, alignment) = (self.base_address, self.alignment);
+        let first = self.first_bytes();
+        let mut start = if self.targets.is_empty() {
+            bytes.len()
+        } else {
+            from
+        };
+        core::iter::from_fn(move || loop {
+            let candidate = match first {
+                Some(first) => {
+                    start
+                        + bytes
+                            .get(start..)?
+                            .iter()
+                            .position(|byte| first.contains(byte))?
+                }
+                None => {
+                    let misalignment = base_address.wrapping_add(start) % alignment;
+                    let candidate = start.checked_add((alignment - misalignment) % alignment)?;
+                    if candidate >= bytes.len() {
+                        return None;
+                    }
+                    candidate
+                }
+            };
+            start = candidate + 1;
+            if base_address
+                .wrapping_add(candidate)
+                .is_multiple_of(alignment)
+            {
+                return Some(candidate);
+            }
+        })
+    }
 }
 
 impl<Endian: ByteOrder> XRefFinder for AbsoluteFinder<Endian> {
     fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
-        if bytes.len() - offset < size_of::<usize>() {
+        if !self.is_aligned(offset) {
             return false;
         }
-        does_match_absolute::<Endian>(bytes, offset, self.target)
+        (fits(bytes, offset, self.pointer_width)
+            && self.targets.contains(self.read_pointer(bytes, offset)))
+            || self.matches_sign_extended(bytes, offset)
+    }
+
+    fn match_width(&self) -> usize {
+        self.pointer_width
+    }
+
+    fn min_bytes(&self) -> usize {
+        if self.sign_extended {
+            self.pointer_width.min(size_of::<i32>())
+        } else {
+            self.pointer_width
+        }
+    }
+
+    fn may_contain(&self, bytes: &[u8]) -> bool {
+        match self.first_bytes() {
+            Some(first) => bytes.iter().any(|byte| first.contains(byte)),
+            None => !self.targets.is_empty(),
+        }
+    }
+
+    fn next_from(&self, bytes: &[u8], start: usize) -> Option<usize> {
+        self.candidates(bytes, start)
+            .find(|&i| self.does_match(bytes, i))
+    }
+
+    fn all(&self, bytes: &[u8]) -> impl Iterator<Item = usize> {
+        self.candidates(bytes, 0)
+            .filter(move |&i| self.does_match(bytes, i))
     }
 }
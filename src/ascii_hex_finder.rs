@@ -0,0 +1,91 @@
+use crate::XRefFinder;
+
+#[derive(Debug, Clone, Copy)]
+pub struct AsciiHexFinder {
+    target: usize,
+    digits: usize,
+}
+
+impl AsciiHexFinder {
+    /// Creates a new AsciiHexFinder, that can then find the textual hexadecimal representation of `target`
+    ///
+    /// The digits are matched case-insensitively and may be prefixed by `0x` or `0X`, leading zeros are not matched.
+    /// The text must not be surrounded by further hexadecimal digits, "1deadbeef" is not a reference to 0xDEADBEEF.
+    ///
+    /// Arguments:
+    ///
+    /// * `target`: The value, which the text should represent
+    pub fn new(target: usize) -> Self {
+        let bits = usize::BITS - target.leading_zeros();
+        Self {
+            target,
+            digits: (bits as usize).div_ceil(4).max(1),
+        }
+    }
+
+    fn digits_match(&self, bytes: &[u8], offset: usize) -> bool {
+        let Some(text) = offset
+            .checked_add(self.digits)
+            .and_then(|end| bytes.get(offset..end))
+        else {
+            return false;
+        };
+        text.iter().enumerate().all(|(i, &digit)| {
+            let nibble = (self.target >> ((self.digits - i - 1) * 4)) & 0xF;
+            (digit as char).to_digit(16) == Some(nibble as u32)
+        })
+    }
+
+    /// Returns the length of the text at `offset`, including the `0x` prefix if present
+    pub fn match_at(&self, bytes: &[u8], offset: usize) -> Option<usize> {
+        let is_hex_digit = |position: usize| bytes.get(position).is_some_and(u8::is_ascii_hexdigit);
+        let prefixed = bytes
+            .get(offset..)
+            .is_some_and(|rest| rest.starts_with(b"0x") || rest.starts_with(b"0X"));
+        let length = if prefixed && self.digits_match(bytes, offset + 2) {
+            self.digits + 2
+        } else if self.digits_match(bytes, offset) {
+            // A prefixed reference is reported at its prefix, not at its digits
+            let after_prefix = offset
+                .checked_sub(1)
+                .is_some_and(|previous| matches!(bytes[previous], b'x' | b'X'));
+            // Neither is the leading 0 of a prefix, e.g. in "0x1234" for 0
+            let before_prefix = matches!(bytes.get(offset + self.digits), Some(b'x' | b'X'));
+            if after_prefix || before_prefix {
+                return None;
+            }
+            self.digits
+        } else {
+            return None;
+        };
+        if offset.checked_sub(1).is_some_and(is_hex_digit) || is_hex_digit(offset + length) {
+            return None;
+        }
+        Some(length)
+    }
+}
+
+impl XRefFinder for AsciiHexFinder {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        self.match_at(bytes, offset).is_some()
+    }
+
+    fn match_width(&self) -> usize {
+        // Including the `0x` prefix
+        self.digits + 2
+    }
+
+    fn min_bytes(&self) -> usize {
+        self.digits
+    }
+
+    fn lookbehind(&self) -> usize {
+        // The digit or `x` before the text
+        1
+    }
+
+    fn lookahead(&self) -> usize {
+        // The digit after a prefixed text
+        1
+    }
+}
@@ -0,0 +1,86 @@
+use byteorder::ByteOrder;
+use core::{marker::PhantomData, mem::size_of};
+
+use crate::{read::fits, relative_finder::does_match_relative, XRefFinder};
+
+/// The mnemonic of a branch, which ends in a relative offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefMnemonic {
+    /// `E8`
+    Call,
+    /// `E9`
+    Jmp,
+    /// `0F 80` to `0F 8F`
+    Jcc,
+}
+
+/// The length of the longest opcode, which `branch_mnemonic` recognizes
+pub(crate) const BRANCH_OPCODE_LENGTH: usize = 2;
+
+/// Returns the mnemonic of the branch, whose opcode is formed by the bytes before `offset`
+pub(crate) fn branch_mnemonic(bytes: &[u8], offset: usize) -> Option<RefMnemonic> {
+    let opcode = |distance: usize| offset.checked_sub(distance).and_then(|i| bytes.get(i));
+    match opcode(1) {
+        Some(0xE8) => Some(RefMnemonic::Call),
+        Some(0xE9) => Some(RefMnemonic::Jmp),
+        Some(0x80..=0x8F) if opcode(2) == Some(&0x0F) => Some(RefMnemonic::Jcc),
+        _ => None,
+    }
+}
+
+/// Checks if the bytes before `offset` form the opcode of a branch, which ends in a relative offset
+///
+/// Known opcodes are `E8` (call), `E9` (jmp) and `0F 80` to `0F 8F` (jcc)
+pub(crate) fn is_branch_opcode(bytes: &[u8], offset: usize) -> bool {
+    branch_mnemonic(bytes, offset).is_some()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CodeHeuristicRelativeFinder<Endian: ByteOrder> {
+    base_address: usize,
+    target: usize,
+    endian: PhantomData<Endian>,
+}
+
+impl<Endian: ByteOrder> CodeHeuristicRelativeFinder<Endian> {
+    /// Creates a new CodeHeuristicRelativeFinder, that can then find relative cross references of branches
+    ///
+    /// This is a heuristic: A relative match is only accepted, if it is preceded by the opcode of a call, jmp or jcc.
+    /// Branches, that are encoded differently (e.g. with prefixes or indirectly), are not found.
+    ///
+    /// Arguments:
+    ///
+    /// * `base_address`: Refer to `RelativeFinder`
+    /// * `target`: The address, which the branch should lead to
+    pub fn new(base_address: usize, target: usize) -> Self {
+        Self {
+            base_address,
+            target,
+            endian: PhantomData,
+        }
+    }
+}
+
+impl<Endian: ByteOrder> XRefFinder for CodeHeuristicRelativeFinder<Endian> {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        if !fits(bytes, offset, size_of::<i32>()) || !is_branch_opcode(bytes, offset) {
+            return false;
+        }
+        does_match_relative::<Endian>(
+            bytes,
+            offset,
+            self.base_address,
+            size_of::<i32>(),
+            0,
+            self.target,
+        )
+    }
+
+    fn match_width(&self) -> usize {
+        size_of::<i32>()
+    }
+
+    fn lookbehind(&self) -> usize {
+        BRANCH_OPCODE_LENGTH
+    }
+}
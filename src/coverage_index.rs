@@ -0,0 +1,24 @@
+use alloc::vec::Vec;
+
+/// The references of a buffer, prepared for containment queries, refer to `XRefFinder::build_coverage_index`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageIndex {
+    offsets: Vec<usize>,
+    width: usize,
+}
+
+impl CoverageIndex {
+    /// Creates a new CoverageIndex out of the sorted `offsets` of references, which occupy `width` bytes
+    pub(crate) fn new(offsets: Vec<usize>, width: usize) -> Self {
+        Self { offsets, width }
+    }
+
+    /// Returns the offset of the reference, whose bytes cover `pos`
+    ///
+    /// If several references overlap at `pos`, the last one is returned
+    pub fn contains(&self, pos: usize) -> Option<usize> {
+        let index = self.offsets.partition_point(|&offset| offset <= pos);
+        let offset = *self.offsets.get(index.checked_sub(1)?)?;
+        (pos - offset < self.width).then_some(offset)
+    }
+}
@@ -0,0 +1,29 @@
+use byteorder::ByteOrder;
+
+use crate::read::{read_addr, read_disp};
+
+/// Decodes the values stored in a scanned buffer
+///
+/// Every `ByteOrder` is a decoder, which reads plain integers in its byte order.
+/// Implement this for architecture specific encodings to reuse the finders and their traversal methods.
+pub trait Decoder {
+    /// Reads a sign-extended displacement of `width` bytes at `offset`
+    ///
+    /// The caller ensures that `offset + width` is within `bytes`
+    fn read_displacement(bytes: &[u8], offset: usize, width: usize) -> isize;
+
+    /// Reads a zero-extended absolute address of `width` bytes at `offset`
+    ///
+    /// The caller ensures that `offset + width` is within `bytes`
+    fn read_absolute(bytes: &[u8], offset: usize, width: usize) -> usize;
+}
+
+impl<Endian: ByteOrder> Decoder for Endian {
+    fn read_displacement(bytes: &[u8], offset: usize, width: usize) -> isize {
+        read_disp::<Endian>(bytes, offset, width)
+    }
+
+    fn read_absolute(bytes: &[u8], offset: usize, width: usize) -> usize {
+        read_addr::<Endian>(bytes, offset, width)
+    }
+}
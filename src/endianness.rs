@@ -0,0 +1,149 @@
+use byteorder::{BigEndian, LittleEndian};
+
+use crate::{matches::resume_iter, AbsoluteFinder, RelativeFinder, XRefFinder};
+
+/// A byte order, which is chosen at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+impl Endianness {
+    /// The byte order of the target, that this crate is compiled for
+    pub const fn native() -> Self {
+        if cfg!(target_endian = "big") {
+            Self::Big
+        } else {
+            Self::Little
+        }
+    }
+}
+
+/// A finder, whose byte order has been chosen at runtime
+///
+/// Both variants are the same finder, only monomorphized for a different byte order.
+/// Every call is dispatched to the chosen one.
+#[derive(Debug, Clone, Copy)]
+pub enum EndianFinder<L, B> {
+    Little(L),
+    Big(B),
+}
+
+impl<L: XRefFinder, B: XRefFinder> EndianFinder<L, B> {
+    /// The byte order, which has been chosen
+    pub fn endianness(&self) -> Endianness {
+        match self {
+            Self::Little(_) => Endianness::Little,
+            Self::Big(_) => Endianness::Big,
+        }
+    }
+}
+
+impl<L: XRefFinder, B: XRefFinder> XRefFinder for EndianFinder<L, B> {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        match self {
+            Self::Little(finder) => finder.does_match(bytes, offset),
+            Self::Big(finder) => finder.does_match(bytes, offset),
+        }
+    }
+
+    fn match_width(&self) -> usize {
+        match self {
+            Self::Little(finder) => finder.match_width(),
+            Self::Big(finder) => finder.match_width(),
+        }
+    }
+
+    fn min_bytes(&self) -> usize {
+        match self {
+            Self::Little(finder) => finder.min_bytes(),
+            Self::Big(finder) => finder.min_bytes(),
+        }
+    }
+
+    fn lookbehind(&self) -> usize {
+        match self {
+            Self::Little(finder) => finder.lookbehind(),
+            Self::Big(finder) => finder.lookbehind(),
+        }
+    }
+
+    fn lookahead(&self) -> usize {
+        match self {
+            Self::Little(finder) => finder.lookahead(),
+            Self::Big(finder) => finder.lookahead(),
+        }
+    }
+
+    fn may_contain(&self, bytes: &[u8]) -> bool {
+        match self {
+            Self::Little(finder) => finder.may_contain(bytes),
+            Self::Big(finder) => finder.may_contain(bytes),
+        }
+    }
+
+    fn next_from(&self, bytes: &[u8], start: usize) -> Option<usize> {
+        match self {
+            Self::Little(finder) => finder.next_from(bytes, start),
+            Self::Big(finder) => finder.next_from(bytes, start),
+        }
+    }
+
+    fn count(&self, bytes: &[u8]) -> usize {
+        match self {
+            Self::Little(finder) => finder.count(bytes),
+            Self::Big(finder) => finder.count(bytes),
+        }
+    }
+
+    fn all(&self, bytes: &[u8]) -> impl Iterator<Item = usize> {
+        // Resumes the search of the chosen finder, so it can use its faster implementation
+        resume_iter(self, bytes)
+    }
+}
+
+/// An `AbsoluteFinder`, whose byte order has been chosen at runtime
+pub type RuntimeAbsoluteFinder =
+    EndianFinder<AbsoluteFinder<LittleEndian>, AbsoluteFinder<BigEndian>>;
+
+/// A `RelativeFinder`, whose byte order has been chosen at runtime
+pub type RuntimeRelativeFinder =
+    EndianFinder<RelativeFinder<LittleEndian>, RelativeFinder<BigEndian>>;
+
+impl AbsoluteFinder<LittleEndian> {
+    /// Creates a new AbsoluteFinder, whose byte order is chosen at runtime
+    ///
+    /// For arguments refer to `new`
+    pub fn new_with_endian(target: usize, endianness: Endianness) -> RuntimeAbsoluteFinder {
+        match endianness {
+            Endianness::Little => EndianFinder::Little(AbsoluteFinder::new(target)),
+            Endianness::Big => EndianFinder::Big(AbsoluteFinder::new(target)),
+        }
+    }
+}
+
+impl RelativeFinder<LittleEndian> {
+    /// Creates a new RelativeFinder, whose byte order is chosen at runtime
+    ///
+    /// For arguments refer to `new`
+    pub fn new_with_endian(
+        base_address: usize,
+        instruction_length: usize,
+        target: usize,
+        endianness: Endianness,
+    ) -> RuntimeRelativeFinder {
+        match endianness {
+            Endianness::Little => EndianFinder::Little(RelativeFinder::new(
+                base_address,
+                instruction_length,
+                target,
+            )),
+            Endianness::Big => EndianFinder::Big(RelativeFinder::new(
+                base_address,
+                instruction_length,
+                target,
+            )),
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use byteorder::ByteOrder;
+use core::{fmt, marker::PhantomData, mem::size_of};
+
+use crate::{
+    matches::resume_iter, relative_finder::MAX_INSTRUCTION_LENGTH, AbsoluteFinder, PointerWidth,
+    Prefer, RefMnemonic, RelativeAndAbsoluteFinder, RelativeFinder, XRefFinder,
+};
+
+/// The options passed to a `FinderBuilder` don't describe a finder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildError {
+    /// No target has been set
+    MissingTarget,
+    /// Relative options have been set, but no base address
+    MissingBaseAddress,
+    /// The alignment is 0
+    ZeroAlignment,
+    /// An instruction length is larger than `MAX_INSTRUCTION_LENGTH`, refer to `RelativeFinder::with_lengths`
+    InvalidInstructionLength,
+    /// The options can't be combined in a single finder, e.g. an alignment and opcodes
+    ConflictingOptions,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::MissingTarget => "no target has been set",
+            Self::MissingBaseAddress => "relative options require a base address",
+            Self::ZeroAlignment => "alignment must not be 0",
+            Self::InvalidInstructionLength => "instruction lengths have to be at most 15",
+            Self::ConflictingOptions => "the options can't be combined in a single finder",
+        })
+    }
+}
+
+impl core::error::Error for BuildError {}
+
+/// The finder, which a `FinderBuilder` built
+#[derive(Debug, Clone, Copy)]
+pub enum BuiltFinder<Endian: ByteOrder> {
+    Absolute(AbsoluteFinder<Endian>),
+    Relative(RelativeFinder<Endian>),
+    RelativeAndAbsolute(RelativeAndAbsoluteFinder<Endian>),
+}
+
+/// Assembles an `AbsoluteFinder`, a `RelativeFinder` or a `RelativeAndAbsoluteFinder` out of named options
+///
+/// The finder is chosen by the options:
+///
+/// * Without relative options (`instruction_length`, `instruction_lengths`, `opcodes`), an `AbsoluteFinder` is built
+/// * With relative options, a `RelativeFinder` is built
+/// * With `relative_and_absolute`, a `RelativeAndAbsoluteFinder` is built
+#[derive(Debug, Clone, Copy)]
+pub struct FinderBuilder<'a, Endian: ByteOrder> {
+    base_address: Option<usize>,
+    instruction_lengths: Option<&'a [usize]>,
+    /// Set by `instruction_length`, so no slice has to be borrowed for a single length
+    instruction_length: Option<usize>,
+    target: Option<usize>,
+    alignment: Option<usize>,
+    pointer_width: Option<PointerWidth>,
+    opcodes: Option<&'a [RefMnemonic]>,
+    prefer: Option<Prefer>,
+    endian: PhantomData<Endian>,
+}
+
+impl<'a, Endian: ByteOrder> FinderBuilder<'a, Endian> {
+    /// Creates a new FinderBuilder without any options
+    pub fn new() -> Self {
+        Self {
+            base_address: None,
+            instruction_lengths: None,
+            instruction_length: None,
+            target: None,
+            alignment: None,
+            pointer_width: None,
+            opcodes: None,
+            prefer: None,
+            endian: PhantomData,
+        }
+    }
+
+    /// Sets the address, at which the scanned bytes are located. Required by the relative options.
+    pub fn base_address(mut self, base_address: usize) -> Self {
+        self.base_address = Some(base_address);
+        self
+    }
+
+    /// Sets the instruction length of relative references, refer to `RelativeFinder::new`
+    pub fn instruction_length(mut self, instruction_length: usize) -> Self {
+        self.instruction_length = Some(instruction_length);
+        self.instruction_lengths = None;
+        self
+    }
+
+    /// Sets several instruction lengths of relative references, which are all tried, refer to `RelativeFinder::with_lengths`
+    pub fn instruction_lengths(mut self, instruction_lengths: &'a [usize]) -> Self {
+        self.instruction_lengths = Some(instruction_lengths);
+        self.instruction_length = None;
+        self
+    }
+
+    /// Sets the address, which the references should point to. Required.
+    pub fn target(mut self, target: usize) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    /// Sets the alignment of absolute references, refer to `AbsoluteFinder::with_alignment`
+    pub fn alignment(mut self, alignment: usize) -> Self {
+        self.alignment = Some(alignment);
+        self
+    }
+
+    /// Sets the width of absolute references, refer to `AbsoluteFinder::with_pointer_width`
+    pub fn pointer_width(mut self, pointer_width: PointerWidth) -> Self {
+        self.pointer_width = Some(pointer_width);
+        self
+    }
+
+    /// Only accepts relative references, which are preceded by a branch of one of the `kinds`, refer to `RelativeFinder::with_opcodes`
+    pub fn opcodes(mut self, kinds: &'a [RefMnemonic]) -> Self {
+        self.opcodes = Some(kinds);
+        self
+    }
+
+    /// Finds relative and absolute references, refer to `RelativeAndAbsoluteFinder::with_preference`
+    pub fn relative_and_absolute(mut self, prefer: Prefer) -> Self {
+        self.prefer = Some(prefer);
+        self
+    }
+
+    /// Validates the options and builds the finder, which they describe
+    pub fn build(self) -> Result<BuiltFinder<Endian>, BuildError> {
+        let target = self.target.ok_or(BuildError::MissingTarget)?;
+        if self.alignment == Some(0) {
+            return Err(BuildError::ZeroAlignment);
+        }
+        if self
+            .instruction_lengths
+            .unwrap_or_default()
+            .iter()
+            .chain(&self.instruction_length)
+            .any(|&length| length > MAX_INSTRUCTION_LENGTH)
+        {
+            return Err(BuildError::InvalidInstructionLength);
+        }
+        let relative = self.instruction_length.is_some()
+            || self.instruction_lengths.is_some()
+            || self.opcodes.is_some()
+            || self.prefer.is_some();
+        let absolute = self.alignment.is_some() || self.pointer_width.is_some();
+
+        if !relative {
+            let mut finder = AbsoluteFinder::new(target).with_alignment(
+                self.base_address.unwrap_or_default(),
+                self.alignment.unwrap_or(1),
+            );
+            if let Some(pointer_width) = self.pointer_width {
+                finder = finder.with_pointer_width(pointer_width);
+            }
+            return Ok(BuiltFinder::Absolute(finder));
+        }
+        let base_address = self.base_address.ok_or(BuildError::MissingBaseAddress)?;
+        if absolute {
+            return Err(BuildError::ConflictingOptions);
+        }
+
+        if let Some(prefer) = self.prefer {
+            let instruction_length = match self.instruction_lengths {
+                Some(&[length]) => length,
+                Some(_) => return Err(BuildError::ConflictingOptions),
+                None => self.instruction_length.unwrap_or(size_of::<i32>()),
+            };
+            if self.opcodes.is_some() {
+                return Err(BuildError::ConflictingOptions);
+            }
+            let finder = RelativeAndAbsoluteFinder::new(base_address, instruction_length, target)
+                .with_preference(prefer);
+            return Ok(BuiltFinder::RelativeAndAbsolute(finder));
+        }
+
+        let mut finder = match self.instruction_lengths {
+            Some(instruction_lengths) => {
+                RelativeFinder::with_lengths(base_address, instruction_lengths, target)
+            }
+            None => RelativeFinder::new(
+                base_address,
+                self.instruction_length.unwrap_or(size_of::<i32>()),
+                target,
+            ),
+        };
+        if let Some(kinds) = self.opcodes {
+            finder = finder.with_opcodes(kinds);
+        }
+        Ok(BuiltFinder::Relative(finder))
+    }
+}
+
+impl<Endian: ByteOrder> Default for FinderBuilder<'_, Endian> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Endian: ByteOrder> XRefFinder for BuiltFinder<Endian> {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        match self {
+            Self::Absolute(finder) => finder.does_match(bytes, offset),
+            Self::Relative(finder) => finder.does_match(bytes, offset),
+            Self::RelativeAndAbsolute(finder) => finder.does_match(bytes, offset),
+        }
+    }
+
+    fn match_width(&self) -> usize {
+        match self {
+            Self::Absolute(finder) => finder.match_width(),
+            Self::Relative(finder) => finder.match_width(),
+            Self::RelativeAndAbsolute(finder) => finder.match_width(),
+        }
+    }
+
+    fn min_bytes(&self) -> usize {
+        match self {
+            Self::Absolute(finder) => finder.min_bytes(),
+            Self::Relative(finder) => finder.min_bytes(),
+            Self::RelativeAndAbsolute(finder) => finder.min_bytes(),
+        }
+    }
+
+    fn lookbehind(&self) -> usize {
+        match self {
+            Self::Absolute(finder) => finder.lookbehind(),
+            Self::Relative(finder) => finder.lookbehind(),
+            Self::RelativeAndAbsolute(finder) => finder.lookbehind(),
+        }
+    }
+
+    fn lookahead(&self) -> usize {
+        match self {
+            Self::Absolute(finder) => finder.lookahead(),
+            Self::Relative(finder) => finder.lookahead(),
+            Self::RelativeAndAbsolute(finder) => finder.lookahead(),
+        }
+    }
+
+    fn may_contain(&self, bytes: &[u8]) -> bool {
+        match self {
+            Self::Absolute(finder) => finder.may_contain(bytes),
+            Self::Relative(finder) => finder.may_contain(bytes),
+            Self::RelativeAndAbsolute(finder) => finder.may_contain(bytes),
+        }
+    }
+
+    fn next_from(&self, bytes: &[u8], start: usize) -> Option<usize> {
+        match self {
+            Self::Absolute(finder) => finder.next_from(bytes, start),
+            Self::Relative(finder) => finder.next_from(bytes, start),
+            Self::RelativeAndAbsolute(finder) => finder.next_from(bytes, start),
+        }
+    }
+
+    fn all(&self, bytes: &[u8]) -> impl Iterator<Item = usize> {
+        // Resumes the search of the built finder, so it can use its faster implementation
+        resume_iter(self, bytes)
+    }
+}
@@ -0,0 +1,74 @@
+use byteorder::ByteOrder;
+use core::{marker::PhantomData, mem::size_of};
+
+use crate::{
+    absolute_finder::does_match_absolute,
+    read::{fits, read_disp},
+    relative_finder::resolve_relative,
+    XRefFinder,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct GotRelativeFinder<'a, Endian: ByteOrder> {
+    base_address: usize,
+    instruction_length: usize,
+    got_address: usize,
+    got: &'a [u8],
+    target: usize,
+    endian: PhantomData<Endian>,
+}
+
+impl<'a, Endian: ByteOrder> GotRelativeFinder<'a, Endian> {
+    /// Creates a new GotRelativeFinder, that can then find relative cross references going through the global offset table
+    ///
+    /// A match is a relative offset, which leads the processor to a slot in the GOT, that contains `target`.
+    ///
+    /// Arguments:
+    ///
+    /// * `base_address`, `instruction_length`: Refer to `RelativeFinder`
+    /// * `got_address`: The address, at which the global offset table is located
+    /// * `got`: The contents of the global offset table
+    /// * `target`: The address, which the GOT slot should point to
+    pub fn new(
+        base_address: usize,
+        instruction_length: usize,
+        got_address: usize,
+        got: &'a [u8],
+        target: usize,
+    ) -> Self {
+        Self {
+            base_address,
+            instruction_length,
+            got_address,
+            got,
+            target,
+            endian: PhantomData,
+        }
+    }
+
+    /// Returns the offset into the GOT, at which the slot referenced by the relative offset at `offset` lives
+    fn slot(&self, bytes: &[u8], offset: usize) -> Option<usize> {
+        let value = read_disp::<Endian>(bytes, offset, size_of::<i32>());
+        let address = resolve_relative(
+            self.base_address.wrapping_add(offset),
+            self.instruction_length,
+            value,
+        );
+        let slot = address.checked_sub(self.got_address)?;
+        fits(self.got, slot, size_of::<usize>()).then_some(slot)
+    }
+}
+
+impl<Endian: ByteOrder> XRefFinder for GotRelativeFinder<'_, Endian> {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        if !fits(bytes, offset, size_of::<i32>()) {
+            return false;
+        }
+        self.slot(bytes, offset)
+            .is_some_and(|slot| does_match_absolute::<Endian>(self.got, slot, self.target))
+    }
+
+    fn match_width(&self) -> usize {
+        size_of::<i32>()
+    }
+}
@@ -0,0 +1,49 @@
+use byteorder::ByteOrder;
+use core::{marker::PhantomData, mem::size_of};
+
+use crate::{
+    read::{fits, read_addr},
+    XRefFinder,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct IndirectFinder<'a, Endian: ByteOrder> {
+    table: &'a [usize],
+    target: usize,
+    endian: PhantomData<Endian>,
+}
+
+impl<'a, Endian: ByteOrder> IndirectFinder<'a, Endian> {
+    /// Creates a new IndirectFinder, that can then find `u32` indices into `table`, whose entry is `target`
+    ///
+    /// Arguments:
+    ///
+    /// * `table`: Maps the stored indices to addresses, e.g. a vtable
+    /// * `target`: The address, which the table entry should point to
+    pub fn new(table: &'a [usize], target: usize) -> Self {
+        Self {
+            table,
+            target,
+            endian: PhantomData,
+        }
+    }
+
+    /// Returns the index stored at `offset`, if its table entry is the target
+    pub fn match_at(&self, bytes: &[u8], offset: usize) -> Option<usize> {
+        if !fits(bytes, offset, size_of::<u32>()) {
+            return None;
+        }
+        let index = read_addr::<Endian>(bytes, offset, size_of::<u32>());
+        (self.table.get(index) == Some(&self.target)).then_some(index)
+    }
+}
+
+impl<Endian: ByteOrder> XRefFinder for IndirectFinder<'_, Endian> {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        self.match_at(bytes, offset).is_some()
+    }
+
+    fn match_width(&self) -> usize {
+        size_of::<u32>()
+    }
+}
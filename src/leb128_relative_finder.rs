@@ -0,0 +1,83 @@
+use crate::{relative_finder::is_relative_match, XRefFinder};
+
+/// The maximum amount of bytes a 32-bit value takes up in LEB128
+const MAX_LEB128_LEN: usize = 5;
+
+/// Decodes a LEB128 encoded 32-bit value at `offset`, returning the value and the amount of bytes it occupies
+///
+/// Returns `None` if the encoding doesn't end within `bytes` or the value doesn't fit into 32 bits
+pub(crate) fn decode_leb128(bytes: &[u8], offset: usize, signed: bool) -> Option<(isize, usize)> {
+    let mut value = 0i64;
+    let mut shift = 0;
+    for (i, &byte) in bytes.get(offset..)?.iter().take(MAX_LEB128_LEN).enumerate() {
+        value |= ((byte & 0x7F) as i64) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            if signed && byte & 0x40 != 0 {
+                value |= -1 << shift;
+            }
+            let fits = if signed {
+                i32::try_from(value).is_ok()
+            } else {
+                u32::try_from(value).is_ok()
+            };
+            return fits.then_some((value as isize, i + 1));
+        }
+    }
+    None
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Leb128RelativeFinder {
+    base_address: usize,
+    target: usize,
+    signed: bool,
+}
+
+impl Leb128RelativeFinder {
+    /// Creates a new Leb128RelativeFinder, that can then find relative cross references stored as unsigned LEB128
+    ///
+    /// The relative offset is applied to the address directly after the encoded value.
+    ///
+    /// Arguments:
+    ///
+    /// * `base_address`: Refer to `RelativeFinder`
+    /// * `target`: The address, which the reference should point to
+    pub fn new(base_address: usize, target: usize) -> Self {
+        Self {
+            base_address,
+            target,
+            signed: false,
+        }
+    }
+
+    /// Same as `new`, but the relative offsets are stored as signed LEB128
+    pub fn new_signed(base_address: usize, target: usize) -> Self {
+        Self {
+            signed: true,
+            ..Self::new(base_address, target)
+        }
+    }
+}
+
+impl XRefFinder for Leb128RelativeFinder {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        decode_leb128(bytes, offset, self.signed).is_some_and(|(value, len)| {
+            is_relative_match(
+                self.base_address.wrapping_add(offset),
+                len,
+                value,
+                self.target,
+            )
+        })
+    }
+
+    fn match_width(&self) -> usize {
+        MAX_LEB128_LEN
+    }
+
+    fn min_bytes(&self) -> usize {
+        // Small relative offsets are encoded in a single byte
+        1
+    }
+}
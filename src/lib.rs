@@ -5,7 +5,20 @@
 //! ## Supported references
 //!
 //! - Absolute references on 32 and 64 bit
-//! - Relative references on 64 bit (32 bit is omitted due to their rarity)
+//! - 32 bit absolute references inside of 64 bit images
+//! - Sign-extended 32 bit immediates of 64 bit absolute addresses
+//! - 16 bit and `segment:offset` absolute references of real mode
+//! - Absolute values, whose masked bits equal a target
+//! - Absolute values, which are congruent to a residue modulo a value
+//! - Indices into a table of addresses
+//! - Absolute pointers, which point to themselves
+//! - Absolute values written as ASCII hexadecimal text
+//! - Relative references on 32 and 64 bit
+//! - Relative references of call, jmp and jcc instructions
+//! - Relative references through the global offset table on 32 and 64 bit
+//! - Relative references stored as LEB128
+//! - Pointers described by Mach-O rebase opcodes (`macho` feature)
+//! - Displacements of `[base + index * scale + disp]` operands with known register values
 //!
 //! ## Examples
 //!
@@ -21,90 +34,3193 @@
 //! ```
 //!
 
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec::Vec};
+
+use core::{iter, ops::Range};
+
+#[cfg(feature = "alloc")]
+use core::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(feature = "std")]
+use byteorder::{LittleEndian, WriteBytesExt};
+#[cfg(feature = "std")]
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
 
 pub mod absolute_finder;
-#[cfg(target_pointer_width = "64")]
+pub mod ascii_hex_finder;
+pub mod code_heuristic_relative_finder;
+#[cfg(feature = "alloc")]
+pub mod coverage_index;
+pub mod decoder;
+pub mod endianness;
+pub mod finder_builder;
+pub mod got_relative_finder;
+pub mod indirect_finder;
+pub mod leb128_relative_finder;
+#[cfg(feature = "macho")]
+pub mod macho_rebase_finder;
+pub mod masked_finder;
+pub mod matches;
+pub mod mixed_width_multi_finder;
+pub mod modular_finder;
+pub mod multi_target_finder;
+mod read;
 pub mod relative_and_absolute_finder;
-#[cfg(target_pointer_width = "64")]
+pub mod relative_extractor;
 pub mod relative_finder;
+#[cfg(feature = "alloc")]
+pub mod scan_builder;
+pub mod section_scan;
+pub mod segmented_relative_finder;
+pub mod self_pointer_finder;
+pub mod sib_finder;
+pub mod stats;
+#[cfg(feature = "alloc")]
+pub mod streaming_finder;
+mod target_range;
+
+/// The amount of bytes scanned between two checks of the cancellation flag in `XRefFinder::all_cancellable`
+/// or the elapsed time in `XRefFinder::all_timed`
+pub const CANCEL_CHECK_INTERVAL: usize = 4096;
 
 pub trait XRefFinder {
     /// Checks if the `offset` in `bytes` is a reference
     fn does_match(&self, bytes: &[u8], offset: usize) -> bool;
 
+    /// The amount of bytes a reference occupies
+    fn match_width(&self) -> usize;
+
+    /// The smallest amount of bytes, which a reference can occupy
+    ///
+    /// This is smaller than `match_width` for finders, which accept references of different widths.
+    /// Defaults to `match_width`.
+    fn min_bytes(&self) -> usize {
+        self.match_width()
+    }
+
+    /// The amount of bytes before a reference, which `does_match` inspects
+    ///
+    /// This is e.g. the opcode of a branch, which precedes its relative offset. Defaults to 0.
+    fn lookbehind(&self) -> usize {
+        0
+    }
+
+    /// The amount of bytes after a reference of `match_width` bytes, which `does_match` inspects
+    ///
+    /// This is e.g. the byte after a text, which must not continue it. Defaults to 0.
+    fn lookahead(&self) -> usize {
+        0
+    }
+
+    /// Quickly checks if a reference could start in `bytes`
+    ///
+    /// Returning `true` doesn't guarantee a reference, but returning `false` has to guarantee that there is none.
+    /// The default implementation can't rule anything out and always returns `true`.
+    fn may_contain(&self, bytes: &[u8]) -> bool {
+        let _ = bytes;
+        true
+    }
+
     /// Finds the next reference
     fn next(&self, bytes: &[u8]) -> Option<usize> {
-        (0..=bytes.len()).find(|&i| self.does_match(bytes, i))
+        self.next_from(bytes, 0)
+    }
+
+    /// Finds the next reference at or after `start`
+    ///
+    /// Unlike searching a subslice, this keeps the offsets relative to the start of `bytes`,
+    /// which is required for finders that depend on the location of a reference (e.g. `RelativeFinder`)
+    fn next_from(&self, bytes: &[u8], start: usize) -> Option<usize> {
+        (start..bytes.len()).find(|&i| self.does_match(bytes, i))
+    }
+
+    /// Finds the next reference before the first `sentinel` byte
+    ///
+    /// The reference has to end before the sentinel, so this never reads the sentinel or anything after it
+    fn next_until_byte(&self, bytes: &[u8], sentinel: u8) -> Option<usize> {
+        let end = bytes
+            .iter()
+            .position(|&byte| byte == sentinel)
+            .unwrap_or(bytes.len());
+        self.next(&bytes[..end])
     }
 
     /// Finds the previous reference
     fn prev(&self, bytes: &[u8]) -> Option<usize> {
-        (0..=bytes.len())
+        self.prev_from(bytes, bytes.len())
+    }
+
+    /// Finds the previous reference before `before`
+    ///
+    /// Refer to `next_from`
+    fn prev_from(&self, bytes: &[u8], before: usize) -> Option<usize> {
+        (0..before.min(bytes.len()))
             .rev()
             .find(|&i| self.does_match(bytes, i))
-            .map(|offset| bytes.len() - offset - 1)
+    }
+
+    /// Finds the previous reference, which ends before `pos`
+    ///
+    /// This is the largest offset of a reference, so that `offset + match_width() <= pos`
+    fn prev_ending_before(&self, bytes: &[u8], pos: usize) -> Option<usize> {
+        let end = pos.min(bytes.len()).checked_sub(self.match_width())?;
+        (0..=end).rev().find(|&i| self.does_match(bytes, i))
     }
 
     // Finds all references in the `bytes` slice
-    fn all(&self, bytes: &[u8]) -> impl Iterator<Item = usize> {
-        (0..=bytes.len()).filter(|&i| self.does_match(bytes, i))
+    fn all(&self, bytes: &[u8]) -> impl Iterator<Item = usize>
+    where
+        Self: Sized,
+    {
+        (0..bytes.len()).filter(|&i| self.does_match(bytes, i))
+    }
+
+    /// Finds all references in the `bytes` slice, as a named iterator, that can be stored
+    ///
+    /// The iterator can also be consumed from the back
+    fn matches<'a>(&'a self, bytes: &'a [u8]) -> Matches<'a, Self>
+    where
+        Self: Sized,
+    {
+        Matches::new(self, bytes)
+    }
+
+    /// Finds all references in the `bytes` slice, starting at the end
+    ///
+    /// The offsets, at which less than `min_bytes` are left, are skipped, so the first reference is reached without scanning the tail
+    fn all_rev(&self, bytes: &[u8]) -> impl Iterator<Item = usize>
+    where
+        Self: Sized,
+    {
+        (0..(bytes.len() + 1).saturating_sub(self.min_bytes().max(1)))
+            .rev()
+            .filter(|&i| self.does_match(bytes, i))
+    }
+
+    /// Collects all references into a `Vec`
+    #[cfg(feature = "alloc")]
+    fn find_all(&self, bytes: &[u8]) -> Vec<usize>
+    where
+        Self: Sized,
+    {
+        self.all(bytes).collect()
+    }
+
+    /// Collects the addresses (`base_address + offset`) of all references into a `Vec`
+    ///
+    /// `base_address` is the address, at which `bytes` is located.
+    /// References, whose address would leave the address space, are skipped.
+    #[cfg(feature = "alloc")]
+    fn find_all_addresses(&self, bytes: &[u8], base_address: usize) -> Vec<usize>
+    where
+        Self: Sized,
+    {
+        self.all(bytes)
+            .filter_map(|offset| base_address.checked_add(offset))
+            .collect()
+    }
+
+    /// Collects all references into `scratch`, which is cleared first, and returns their amount
+    ///
+    /// This allows reusing one allocation across many scans
+    #[cfg(feature = "alloc")]
+    fn find_all_in(&self, bytes: &[u8], scratch: &mut Vec<usize>) -> usize
+    where
+        Self: Sized,
+    {
+        scratch.clear();
+        scratch.extend(self.all(bytes));
+        scratch.len()
+    }
+
+    /// Finds all references in the `len` bytes at `ptr`, without reading at or past `readable_end`
+    ///
+    /// References, whose `match_width` bytes would extend past `readable_end`, are skipped.
+    /// This allows scanning memory, which is followed by an unmapped page.
+    ///
+    /// # Safety
+    ///
+    /// The memory from `ptr` up to the smaller one of `ptr + len` and `readable_end` must be valid for reads
+    /// and must not be mutated, as long as the returned iterator is alive.
+    /// `readable_end` doesn't need to be valid for reads itself. If it lies before `ptr`, nothing is read.
+    unsafe fn all_raw_bounded(
+        &self,
+        ptr: *const u8,
+        len: usize,
+        readable_end: *const u8,
+    ) -> impl Iterator<Item = *const u8> + '_
+    where
+        Self: Sized,
+    {
+        let readable = (readable_end as usize)
+            .saturating_sub(ptr as usize)
+            .min(len);
+        // SAFETY: The caller guarantees, that the first `readable` bytes at `ptr` are valid for reads
+        let bytes = unsafe { core::slice::from_raw_parts(ptr, readable) };
+        let width = self.match_width();
+        (0..readable)
+            .filter(move |&i| bytes.len() - i >= width && self.does_match(bytes, i))
+            .map(move |i| ptr.wrapping_add(i))
+    }
+
+    /// Finds all references, until `budget` has elapsed
+    ///
+    /// The elapsed time is checked every `CANCEL_CHECK_INTERVAL` bytes.
+    /// Returns the references found up to that point and whether the whole buffer has been scanned.
+    #[cfg(feature = "std")]
+    fn all_timed(&self, bytes: &[u8], budget: Duration) -> (Vec<usize>, bool)
+    where
+        Self: Sized,
+    {
+        let start_time = Instant::now();
+        let mut matches = Vec::new();
+        for start in (0..bytes.len()).step_by(CANCEL_CHECK_INTERVAL) {
+            if start_time.elapsed() >= budget {
+                return (matches, false);
+            }
+            let end = bytes.len().min(start + CANCEL_CHECK_INTERVAL);
+            matches.extend((start..end).filter(|&i| self.does_match(bytes, i)));
+        }
+        (matches, true)
+    }
+
+    /// Finds all references using one thread per available core
+    ///
+    /// The buffer is split into one range of offsets per thread. Every thread checks its offsets against the whole buffer,
+    /// so references crossing the end of a range are still found and relative references resolve as in `all`.
+    /// Returns the same references as `all`, in ascending order.
+    #[cfg(feature = "std")]
+    fn par_all(&self, bytes: &[u8]) -> Vec<usize>
+    where
+        Self: Sized + Sync,
+    {
+        let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+        let chunk_size = bytes.len().div_ceil(threads).max(CANCEL_CHECK_INTERVAL);
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..bytes.len())
+                .step_by(chunk_size)
+                .map(|start| {
+                    let end = bytes.len().min(start + chunk_size);
+                    scope.spawn(move || {
+                        (start..end)
+                            .filter(|&i| self.does_match(bytes, i))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("scanning thread panicked"))
+                .collect()
+        })
+    }
+
+    /// Finds all references and pairs each with its index among them
+    ///
+    /// Returns the total amount of references and an iterator over `(index, offset)`.
+    /// The references are collected up front to compute the total.
+    #[cfg(feature = "alloc")]
+    fn all_enumerated_total(&self, bytes: &[u8]) -> (usize, impl Iterator<Item = (usize, usize)>)
+    where
+        Self: Sized,
+    {
+        let matches: Vec<usize> = self.all(bytes).collect();
+        (matches.len(), matches.into_iter().enumerate())
+    }
+
+    /// Finds all references, until `cancel` is set
+    ///
+    /// `cancel` is checked every `CANCEL_CHECK_INTERVAL` bytes, the references found up to that point are returned
+    #[cfg(feature = "alloc")]
+    fn all_cancellable(&self, bytes: &[u8], cancel: &AtomicBool) -> Vec<usize>
+    where
+        Self: Sized,
+    {
+        let mut matches = Vec::new();
+        for start in (0..bytes.len()).step_by(CANCEL_CHECK_INTERVAL) {
+            if cancel.load(Ordering::Relaxed) {
+                break;
+            }
+            let end = bytes.len().min(start + CANCEL_CHECK_INTERVAL);
+            matches.extend((start..end).filter(|&i| self.does_match(bytes, i)));
+        }
+        matches
+    }
+
+    /// Scans several views of `(base_address, bytes)` and returns the sorted, deduplicated addresses of all references
+    ///
+    /// The address of a reference is `base_address + offset`, so references found in overlapping views are reported once.
+    /// The finder is used unchanged for every view, finders depending on a base address have to be configured accordingly
+    /// (e.g. with `RelativeFinder::all_with_base`).
+    #[cfg(feature = "alloc")]
+    fn scan_dedup_by_address(&self, views: &[(usize, &[u8])]) -> Vec<usize>
+    where
+        Self: Sized,
+    {
+        let mut addresses = views
+            .iter()
+            .flat_map(|&(base_address, bytes)| {
+                self.all(bytes)
+                    .filter_map(move |offset| base_address.checked_add(offset))
+            })
+            .collect::<Vec<_>>();
+        addresses.sort_unstable();
+        addresses.dedup();
+        addresses
+    }
+
+    /// Finds all references and indexes them, so it can be queried which reference covers a position
+    #[cfg(feature = "alloc")]
+    fn build_coverage_index(&self, bytes: &[u8]) -> CoverageIndex
+    where
+        Self: Sized,
+    {
+        CoverageIndex::new(self.all(bytes).collect(), self.match_width())
+    }
+
+    /// Writes all references to `out`
+    ///
+    /// The format is the amount of references followed by their offsets, each as a little endian `u64`
+    #[cfg(feature = "std")]
+    fn write_matches<W: Write>(&self, bytes: &[u8], out: &mut W) -> io::Result<()>
+    where
+        Self: Sized,
+    {
+        let matches = self.all(bytes).collect::<Vec<_>>();
+        out.write_u64::<LittleEndian>(matches.len() as u64)?;
+        for offset in matches {
+            out.write_u64::<LittleEndian>(offset as u64)?;
+        }
+        Ok(())
+    }
+
+    /// Formats a hex dump of every reference, which includes `context` bytes before and after it
+    ///
+    /// Each reference is printed on its own line, starting with its offset. The bytes of the reference are
+    /// enclosed in brackets and followed by their ASCII representation, non-printable bytes are shown as `.`.
+    #[cfg(feature = "std")]
+    fn dump_matches(&self, bytes: &[u8], context: usize) -> String
+    where
+        Self: Sized,
+    {
+        use core::fmt::Write as _;
+
+        let mut dump = String::new();
+        for offset in self.all(bytes) {
+            let window = offset..bytes.len().min(offset + self.match_width());
+            let shown = offset.saturating_sub(context)..bytes.len().min(window.end + context);
+            write!(dump, "{offset:08x}:").unwrap();
+            for i in shown.clone() {
+                dump.push_str(if i == window.start { " [" } else { " " });
+                write!(dump, "{:02x}", bytes[i]).unwrap();
+                if i + 1 == window.end {
+                    dump.push(']');
+                }
+            }
+            dump.push_str("  |");
+            dump.extend(bytes[shown].iter().map(|&byte| {
+                if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                }
+            }));
+            dump.push_str("|\n");
+        }
+        dump
+    }
+
+    /// Finds all references, skipping every reference, which starts less than `min_spacing` bytes after the last reported one
+    ///
+    /// This only reports the first reference of each cluster
+    fn all_spaced(&self, bytes: &[u8], min_spacing: usize) -> impl Iterator<Item = usize>
+    where
+        Self: Sized,
+    {
+        let mut next_allowed = 0;
+        self.all(bytes).filter(move |&offset| {
+            if offset < next_allowed {
+                return false;
+            }
+            next_allowed = offset.saturating_add(min_spacing);
+            true
+        })
+    }
+
+    /// Finds all references and pairs them with the distance to the next reference, which is `None` for the last one
+    fn all_with_next_gap(&self, bytes: &[u8]) -> impl Iterator<Item = (usize, Option<usize>)>
+    where
+        Self: Sized,
+    {
+        let mut matches = self.all(bytes).peekable();
+        iter::from_fn(move || {
+            let offset = matches.next()?;
+            Some((offset, matches.peek().map(|next| next - offset)))
+        })
+    }
+
+    /// Finds all references and pairs them with the amount of bytes, which follow the reference
+    ///
+    /// This is `bytes.len() - (offset + match_width())`, or 0 if the reference is cut off by the end of `bytes`
+    fn all_with_remaining<'a>(
+        &'a self,
+        bytes: &'a [u8],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a
+    where
+        Self: Sized,
+    {
+        let width = self.match_width();
+        self.all(bytes)
+            .map(move |offset| (offset, bytes.len().saturating_sub(offset + width)))
+    }
+
+    /// Finds all references and returns them both as file offset and as virtual address
+    ///
+    /// Yields `(file_base + offset, virtual_base + offset)`, where `file_base` is the position of `bytes` in the file
+    /// and `virtual_base` is the address, at which `bytes` is loaded.
+    /// References, whose file offset or address would leave the address space, are skipped.
+    fn all_dual<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        file_base: usize,
+        virtual_base: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a
+    where
+        Self: Sized,
+    {
+        self.all(bytes).filter_map(move |offset| {
+            Some((
+                file_base.checked_add(offset)?,
+                virtual_base.checked_add(offset)?,
+            ))
+        })
+    }
+
+    /// Finds all references and scores how likely they are to be at a real instruction boundary
+    ///
+    /// Without disassembling, a reference is considered more credible, if no other reference starts 1 or 2 bytes earlier,
+    /// which would consume its bytes, and if it is preceded by the opcode of a branch (refer to `CodeHeuristicRelativeFinder`).
+    /// Each of these three criteria adds 1 to the score, so it ranges from 0 to 3.
+    fn all_credible<'a>(&'a self, bytes: &'a [u8]) -> impl Iterator<Item = (usize, u8)> + 'a
+    where
+        Self: Sized,
+    {
+        self.all(bytes).map(move |offset| {
+            let consumed = |distance: usize| {
+                offset
+                    .checked_sub(distance)
+                    .is_some_and(|earlier| self.does_match(bytes, earlier))
+            };
+            let score = !consumed(1) as u8
+                + !consumed(2) as u8
+                + code_heuristic_relative_finder::is_branch_opcode(bytes, offset) as u8;
+            (offset, score)
+        })
+    }
+
+    /// Counts all references without collecting their offsets
+    ///
+    /// Finders may override this with a faster implementation, that doesn't track positions.
+    /// By default this resumes `next_from` after every reference, so it benefits from faster searches of finders,
+    /// e.g. `AbsoluteFinder` only verifies offsets, which start with the first byte of the target.
+    fn count(&self, bytes: &[u8]) -> usize {
+        let mut count = 0;
+        let mut start = 0;
+        while let Some(offset) = self.next_from(bytes, start) {
+            count += 1;
+            start = offset + 1;
+        }
+        count
+    }
+
+    /// Checks if there is any reference in `bytes`
+    fn any(&self, bytes: &[u8]) -> bool {
+        self.may_contain(bytes) && self.next(bytes).is_some()
+    }
+
+    /// Calls `f` with the offset of every reference
+    fn for_each_match(&self, bytes: &[u8], f: impl FnMut(usize))
+    where
+        Self: Sized,
+    {
+        self.all(bytes).for_each(f);
+    }
+
+    /// Computes statistics about all references in a single pass
+    fn stats(&self, bytes: &[u8]) -> MatchStats
+    where
+        Self: Sized,
+    {
+        self.all(bytes)
+            .fold(MatchStats::default(), MatchStats::push)
+    }
+
+    /// Finds the widest range of bytes, which isn't covered by any reference
+    fn largest_gap(&self, bytes: &[u8]) -> Option<Range<usize>>
+    where
+        Self: Sized,
+    {
+        let width = self.match_width();
+        let mut cursor = 0;
+        let mut largest: Option<Range<usize>> = None;
+        let mut consider = |gap: Range<usize>| {
+            if !gap.is_empty()
+                && largest
+                    .as_ref()
+                    .is_none_or(|largest| gap.len() > largest.len())
+            {
+                largest = Some(gap);
+            }
+        };
+        for offset in self.all(bytes) {
+            consider(cursor..offset);
+            cursor = cursor.max(offset + width);
+        }
+        consider(cursor..bytes.len());
+        largest
+    }
+
+    /// Finds all references and pairs them with their relative position in the `bytes` slice
+    ///
+    /// The position is `offset / bytes.len()`, which is in `[0, 1)` and increases with each reference.
+    /// This only needs basic float arithmetic, which is available in `core`.
+    fn all_with_progress(&self, bytes: &[u8]) -> impl Iterator<Item = (usize, f32)>
+    where
+        Self: Sized,
+    {
+        self.all(bytes)
+            .map(|offset| (offset, offset as f32 / bytes.len() as f32))
+    }
+
+    /// Finds all references and pairs them with the `align` sized word, in which they start
+    ///
+    /// The word starts at the largest multiple of `align`, which isn't larger than the offset of the reference.
+    /// It is cut off by the end of `bytes`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is 0
+    fn all_with_aligned_word<'a>(
+        &self,
+        bytes: &'a [u8],
+        align: usize,
+    ) -> impl Iterator<Item = (usize, &'a [u8])>
+    where
+        Self: Sized,
+    {
+        assert!(align != 0, "align must not be 0");
+        self.all(bytes).map(move |offset| {
+            let start = offset - offset % align;
+            (offset, &bytes[start..bytes.len().min(start + align)])
+        })
+    }
+
+    /// Calls `patch` with the offset and the bytes of every reference, so they can be modified in place
+    ///
+    /// The slice handed to `patch` is `match_width` bytes long, unless it is cut off by the end of `bytes`. References are searched after the previous patch has been applied.
+    /// This works with any buffer, that dereferences to `[u8]`, e.g. `Vec<u8>` or `bytes::BytesMut`.
+    fn patch_all(&self, bytes: &mut [u8], mut patch: impl FnMut(usize, &mut [u8]))
+    where
+        Self: Sized,
+    {
+        let width = self.match_width();
+        for i in 0..bytes.len() {
+            if self.does_match(bytes, i) {
+                let end = bytes.len().min(i + width);
+                patch(i, &mut bytes[i..end]);
+            }
+        }
+    }
+
+    /// Counts the references per cache line of `line_size` bytes
+    ///
+    /// Yields `(line_index, match_count)` for every line, in which at least one reference starts
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line_size` is 0
+    fn per_cache_line(&self, bytes: &[u8], line_size: usize) -> impl Iterator<Item = (usize, usize)>
+    where
+        Self: Sized,
+    {
+        assert!(line_size != 0, "line_size must not be 0");
+        let mut matches = self.all(bytes).peekable();
+        iter::from_fn(move || {
+            let line = matches.next()? / line_size;
+            let mut count = 1;
+            while matches
+                .next_if(|&offset| offset / line_size == line)
+                .is_some()
+            {
+                count += 1;
+            }
+            Some((line, count))
+        })
+    }
+
+    /// Finds all references, skipping every page of `page_size` bytes, that can't contain a reference according to `may_contain`
+    ///
+    /// References starting in one page and ending in the next one are still found.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is 0
+    fn all_paged(&self, bytes: &[u8], page_size: usize) -> impl Iterator<Item = usize>
+    where
+        Self: Sized,
+    {
+        let width = self.match_width();
+        (0..bytes.len())
+            .step_by(page_size)
+            .filter(move |&page| {
+                let end = page
+                    .saturating_add(page_size)
+                    .saturating_add(width.saturating_sub(1));
+                self.may_contain(&bytes[page..bytes.len().min(end)])
+            })
+            .flat_map(move |page| {
+                (page..bytes.len().min(page.saturating_add(page_size)))
+                    .filter(|&i| self.does_match(bytes, i))
+            })
+    }
+}
+
+impl<F: XRefFinder + ?Sized> XRefFinder for &F {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        (**self).does_match(bytes, offset)
+    }
+
+    fn match_width(&self) -> usize {
+        (**self).match_width()
+    }
+
+    fn min_bytes(&self) -> usize {
+        (**self).min_bytes()
+    }
+
+    fn lookbehind(&self) -> usize {
+        (**self).lookbehind()
+    }
+
+    fn lookahead(&self) -> usize {
+        (**self).lookahead()
+    }
+
+    fn may_contain(&self, bytes: &[u8]) -> bool {
+        (**self).may_contain(bytes)
     }
+
+    fn next_from(&self, bytes: &[u8], start: usize) -> Option<usize> {
+        (**self).next_from(bytes, start)
+    }
+
+    fn count(&self, bytes: &[u8]) -> usize {
+        (**self).count(bytes)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<F: XRefFinder + ?Sized> XRefFinder for Box<F> {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        (**self).does_match(bytes, offset)
+    }
+
+    fn match_width(&self) -> usize {
+        (**self).match_width()
+    }
+
+    fn min_bytes(&self) -> usize {
+        (**self).min_bytes()
+    }
+
+    fn lookbehind(&self) -> usize {
+        (**self).lookbehind()
+    }
+
+    fn lookahead(&self) -> usize {
+        (**self).lookahead()
+    }
+
+    fn may_contain(&self, bytes: &[u8]) -> bool {
+        (**self).may_contain(bytes)
+    }
+
+    fn next_from(&self, bytes: &[u8], start: usize) -> Option<usize> {
+        (**self).next_from(bytes, start)
+    }
+
+    fn count(&self, bytes: &[u8]) -> usize {
+        (**self).count(bytes)
+    }
+}
+
+/// A finder, which can be cloned behind a `Box<dyn CloneFinder>`
+///
+/// This is implemented for every finder, that is `Clone`.
+#[cfg(feature = "alloc")]
+pub trait CloneFinder: XRefFinder {
+    /// Clones the finder into a new box
+    fn clone_box(&self) -> Box<dyn CloneFinder>;
+}
+
+#[cfg(feature = "alloc")]
+impl<F: XRefFinder + Clone + 'static> CloneFinder for F {
+    fn clone_box(&self) -> Box<dyn CloneFinder> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Clone for Box<dyn CloneFinder> {
+    fn clone(&self) -> Self {
+        (**self).clone_box()
+    }
+}
+
+/// Finds all references in the file at `path`
+///
+/// The offsets are relative to the start of the file.
+/// Memory mapping requires a crate like `memmap2`, which this crate doesn't depend on,
+/// so the whole file is read into memory instead.
+#[cfg(feature = "std")]
+pub fn scan_file<P: AsRef<Path>>(path: P, finder: &impl XRefFinder) -> io::Result<Vec<usize>> {
+    let bytes = fs::read(path)?;
+    Ok(finder.all(&bytes).collect())
 }
 
-pub use absolute_finder::AbsoluteFinder;
-#[cfg(target_pointer_width = "64")]
-pub use relative_and_absolute_finder::RelativeAndAbsoluteFinder;
-#[cfg(target_pointer_width = "64")]
-pub use relative_finder::RelativeFinder;
+pub use absolute_finder::{AbsoluteFinder, PointerWidth};
+pub use ascii_hex_finder::AsciiHexFinder;
+pub use code_heuristic_relative_finder::{CodeHeuristicRelativeFinder, RefMnemonic};
+#[cfg(feature = "alloc")]
+pub use coverage_index::CoverageIndex;
+pub use decoder::Decoder;
+pub use endianness::{EndianFinder, Endianness, RuntimeAbsoluteFinder, RuntimeRelativeFinder};
+pub use finder_builder::{BuildError, BuiltFinder, FinderBuilder};
+pub use got_relative_finder::GotRelativeFinder;
+pub use indirect_finder::IndirectFinder;
+pub use leb128_relative_finder::Leb128RelativeFinder;
+#[cfg(feature = "macho")]
+pub use macho_rebase_finder::MachORebaseFinder;
+pub use masked_finder::MaskedFinder;
+pub use matches::Matches;
+pub use mixed_width_multi_finder::MixedWidthMultiFinder;
+pub use modular_finder::ModularFinder;
+pub use multi_target_finder::MultiTargetFinder;
+pub use relative_and_absolute_finder::{
+    Explanation, Prefer, ReferenceKind, RelativeAndAbsoluteFinder,
+};
+pub use relative_extractor::RelativeExtractor;
+pub use relative_finder::{DisplacementWidth, RelativeFinder};
+#[cfg(feature = "alloc")]
+pub use scan_builder::{ScanBuilder, Scanner};
+pub use section_scan::{scan_sections, Section};
+pub use segmented_relative_finder::SegmentedRelativeFinder;
+pub use self_pointer_finder::SelfPointerFinder;
+pub use sib_finder::SibFinder;
+pub use stats::MatchStats;
+#[cfg(feature = "alloc")]
+pub use streaming_finder::StreamingFinder;
 
 #[cfg(test)]
 mod tests {
-    use byteorder::LittleEndian;
+    use core::cell::Cell;
+
+    use byteorder::{ByteOrder, LittleEndian};
 
     use super::*;
 
+    /// Counts how often `does_match` was called on the inner finder
+    struct CountingFinder<F> {
+        inner: F,
+        calls: Cell<usize>,
+    }
+
+    impl<F> CountingFinder<F> {
+        fn new(inner: F) -> Self {
+            Self {
+                inner,
+                calls: Cell::new(0),
+            }
+        }
+    }
+
+    impl<F: XRefFinder> XRefFinder for CountingFinder<F> {
+        fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+            self.calls.set(self.calls.get() + 1);
+            self.inner.does_match(bytes, offset)
+        }
+
+        fn match_width(&self) -> usize {
+            self.inner.match_width()
+        }
+    }
+
     #[test]
     #[cfg(target_pointer_width = "64")]
     fn check_find_xref() {
-        let bytes = [0x00u8, 0x01, 0x23, 0x45, 0x67];
+        let bytes = [0x00u8, 0x01, 0x23, 0x45, 0x67, 0x00];
         let searcher = RelativeAndAbsoluteFinder::<LittleEndian>::new(0, 4, 0x67452301 + 5);
 
         assert_eq!(searcher.next(&bytes), Some(1));
-        assert_eq!(searcher.prev(&bytes), Some(3));
+        assert_eq!(searcher.prev(&bytes), Some(1));
         assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
     }
 
     #[test]
     #[cfg(target_pointer_width = "64")]
     fn check_find_xref_rel() {
-        let bytes = [0x00u8, 0x01, 0x23, 0x45, 0x67];
+        let bytes = [0x00u8, 0x01, 0x23, 0x45, 0x67, 0x00];
         let searcher = RelativeFinder::<LittleEndian>::new(0, 4, 0x67452301 + 5);
 
         assert_eq!(searcher.next(&bytes), Some(1));
-        assert_eq!(searcher.prev(&bytes), Some(3));
+        assert_eq!(searcher.prev(&bytes), Some(1));
         assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
     }
 
     #[test]
     #[cfg(target_pointer_width = "64")]
     fn check_find_xref_abs() {
-        let bytes = [0x00u8, 0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF];
+        let bytes = [0x00u8, 0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF, 0x00];
         let searcher = AbsoluteFinder::<LittleEndian>::new(0xEFCDAB8967452301);
 
         assert_eq!(searcher.next(&bytes), Some(1));
-        assert_eq!(searcher.prev(&bytes), Some(7));
+        assert_eq!(searcher.prev(&bytes), Some(1));
         assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
     }
 
+    #[test]
+    #[cfg(target_pointer_width = "32")]
+    fn check_find_xref_32_bit() {
+        let bytes = [0x00u8, 0x78, 0x56, 0x34, 0x12, 0xE8, 0x6E, 0x44, 0x34, 0x12];
+        let searcher = RelativeAndAbsoluteFinder::<LittleEndian>::new(0x1000, 4, 0x12345678);
+
+        assert_eq!(searcher.next(&bytes), Some(1));
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1, 6]);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "32")]
+    fn check_find_xref_rel_32_bit() {
+        // call 0x12345678 ; at 0x1001
+        let bytes = [0x00u8, 0xE8, 0x72, 0x46, 0x34, 0x12, 0x00];
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x12345678);
+
+        assert_eq!(searcher.next(&bytes), Some(2));
+        assert_eq!(searcher.prev(&bytes), Some(2));
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [2]);
+    }
+
     #[test]
     #[cfg(target_pointer_width = "32")]
     fn check_find_xref_abs_32_bit() {
-        let bytes = [0x00u8, 0x01, 0x23, 0x45, 0x67];
+        let bytes = [0x00u8, 0x01, 0x23, 0x45, 0x67, 0x00];
         let searcher = AbsoluteFinder::<LittleEndian>::new(0x67452301);
 
         assert_eq!(searcher.next(&bytes), Some(1));
-        assert_eq!(searcher.prev(&bytes), Some(3));
+        assert_eq!(searcher.prev(&bytes), Some(1));
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_find_xref_got() {
+        // mov rax, [rip + 0x1001] ; at 0x1000, leads to the GOT slot at 0x2008
+        let bytes = [0x48u8, 0x8B, 0x05, 0x01, 0x10, 0x00, 0x00];
+        let mut got = [0x00u8; 16];
+        got[8..].copy_from_slice(&0xDEADBEEFu64.to_le_bytes());
+
+        let searcher = GotRelativeFinder::<LittleEndian>::new(0x1000, 4, 0x2000, &got, 0xDEADBEEF);
+        assert_eq!(searcher.next(&bytes), Some(3));
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [3]);
+
+        let searcher = GotRelativeFinder::<LittleEndian>::new(0x1000, 4, 0x2000, &got, 0xCAFEBABE);
+        assert_eq!(searcher.next(&bytes), None);
+    }
+
+    #[test]
+    fn check_stats() {
+        let mut bytes = [0x00u8; 32];
+        for offset in [2, 10, 24] {
+            bytes[offset..offset + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        }
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+
+        let stats = searcher.stats(&bytes);
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.first, Some(2));
+        assert_eq!(stats.last, Some(24));
+        assert_eq!(stats.mean_gap, Some(11.0));
+
+        assert_eq!(searcher.stats(&[]), MatchStats::default());
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_find_xref_rel_with_base() {
+        let bytes = [0xE8u8, 0x00, 0x10, 0x00, 0x00];
+        let searcher = RelativeFinder::<LittleEndian>::new(0, 4, 0x2005);
+
+        assert_eq!(searcher.next(&bytes), None);
+        assert_eq!(searcher.next_with_base(&bytes, 0x1000), Some(1));
+        assert_eq!(
+            searcher.all_with_base(&bytes, 0x1000).collect::<Vec<_>>(),
+            [1]
+        );
+        assert_eq!(searcher.next_with_base(&bytes, 0x2000), None);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_find_xref_rel_directions() {
+        // jmp 0x1010 ; at 0x1000
+        let bytes = [0xE9u8, 0x0B, 0x00, 0x00, 0x00];
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x1010);
+        assert_eq!(searcher.sources_of(&bytes).collect::<Vec<_>>(), [1]);
+        assert_eq!(searcher.destinations_from(&bytes).count(), 0);
+
+        // The same relative offset located at 0xFF2 would land on 0x1001
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0xFF2);
+        assert_eq!(searcher.sources_of(&bytes).count(), 0);
+        assert_eq!(searcher.destinations_from(&bytes).collect::<Vec<_>>(), [1]);
+        let searcher = searcher.with_opcodes(&[RefMnemonic::Call]);
+        assert_eq!(searcher.destinations_from(&bytes).count(), 0);
+        let searcher = searcher.with_opcodes(&[RefMnemonic::Jmp]);
+        assert_eq!(searcher.destinations_from(&bytes).collect::<Vec<_>>(), [1]);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_find_xref_rel_rip_adjust() {
+        let bytes = [0xE8u8, 0x00, 0x10, 0x00, 0x00];
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x2003).with_rip_adjust(-2);
+
+        assert_eq!(searcher.next(&bytes), Some(1));
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
+        assert_eq!(
+            RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x2003).next(&bytes),
+            None
+        );
+
+        // The adjusted displacement wraps around like the address
+        let searcher = RelativeFinder::<LittleEndian>::new(0, 4, 0).with_rip_adjust(isize::MAX);
+        assert!(!searcher.does_match(&[1, 0, 0, 0], 0));
+        let target = 4usize.wrapping_add_signed(isize::MIN);
+        let searcher =
+            RelativeFinder::<LittleEndian>::new(0, 4, target).with_rip_adjust(isize::MAX);
+        assert!(searcher.does_match(&[1, 0, 0, 0], 0));
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_find_xref_rel_wraparound() {
+        // call 0x15 ; at usize::MAX - 0xF, the next instruction is at usize::MAX - 0xA
+        let bytes = [0xE8u8, 0x20, 0x00, 0x00, 0x00];
+        let searcher = RelativeFinder::<LittleEndian>::new(usize::MAX - 0xF, 4, 0x15);
+        assert!(searcher.is_satisfiable(bytes.len()));
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
+
+        // call usize::MAX - 0xA ; at 0, the next instruction is at 5
+        let bytes = [0xE8u8, 0xF0, 0xFF, 0xFF, 0xFF];
+        let searcher = RelativeFinder::<LittleEndian>::new(0, 4, usize::MAX - 0xA);
+        assert!(searcher.is_satisfiable(bytes.len()));
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
+
+        let finder = RelativeAndAbsoluteFinder::<LittleEndian>::new(0, 4, usize::MAX - 0xA);
+        assert_eq!(
+            finder.explain(&bytes, 1).relative_address,
+            Some(usize::MAX - 0xA)
+        );
+        assert_eq!(finder.all(&bytes).collect::<Vec<_>>(), [1]);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_largest_gap() {
+        let mut bytes = [0x00u8; 110];
+        bytes[4..8].copy_from_slice(&(0x1000i32 - 8).to_le_bytes());
+        bytes[100..104].copy_from_slice(&(0x1000i32 - 104).to_le_bytes());
+        let searcher = RelativeFinder::<LittleEndian>::new(0x100000000, 4, 0x100001000);
+
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [4, 100]);
+        assert_eq!(searcher.largest_gap(&bytes), Some(8..100));
+        assert_eq!(searcher.largest_gap(&bytes[..8]), Some(0..4));
+        assert_eq!(searcher.largest_gap(&[]), None);
+    }
+
+    #[test]
+    fn check_find_xref_abs_null() {
+        let bytes = [0xFFu8; 32];
+        let mut zeroed = bytes;
+        zeroed[3..3 + size_of::<usize>()].fill(0x00);
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0);
+
+        assert_eq!(searcher.next(&bytes), None);
+        assert_eq!(searcher.all(&zeroed).collect::<Vec<_>>(), [3]);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_find_xref_null() {
+        let mut bytes = [0xFFu8; 32];
+        bytes[3..11].fill(0x00);
+
+        let searcher = RelativeAndAbsoluteFinder::<LittleEndian>::new(0x1000, 4, 0);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [3]);
+
+        // Resolves to 0 from 0x1000 + 1
+        let bytes = [0xE8u8, 0xFB, 0xEF, 0xFF, 0xFF];
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
+        let searcher = RelativeAndAbsoluteFinder::<LittleEndian>::new(0x1000, 4, 0);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
+
+        let mut got = [0xFFu8; 16];
+        got[8..].fill(0x00);
+        // mov rax, [rip + 0x1001] ; at 0x1000, leads to the GOT slot at 0x2008
+        let bytes = [0x48u8, 0x8B, 0x05, 0x01, 0x10, 0x00, 0x00];
+        let searcher = GotRelativeFinder::<LittleEndian>::new(0x1000, 4, 0x2000, &got, 0);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [3]);
+    }
+
+    #[test]
+    fn check_default_decoder() {
+        let bytes = [0x00u8, 0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF];
+
+        assert_eq!(
+            <LittleEndian as Decoder>::read_displacement(&bytes, 1, 4),
+            LittleEndian::read_i32(&bytes[1..5]) as isize
+        );
+        assert_eq!(
+            <LittleEndian as Decoder>::read_displacement(&bytes, 5, 4),
+            LittleEndian::read_i32(&bytes[5..9]) as isize
+        );
+        assert_eq!(
+            <LittleEndian as Decoder>::read_absolute(&bytes, 1, 4),
+            LittleEndian::read_u32(&bytes[1..5]) as usize
+        );
+        assert_eq!(
+            <LittleEndian as Decoder>::read_absolute(&bytes, 1, 8),
+            LittleEndian::read_u64(&bytes[1..9]) as usize
+        );
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_custom_decoder() {
+        // Stores displacements in units of instructions, each instruction being 4 bytes
+        struct WordDecoder;
+        impl Decoder for WordDecoder {
+            fn read_displacement(bytes: &[u8], offset: usize, width: usize) -> isize {
+                <LittleEndian as Decoder>::read_displacement(bytes, offset, width) * 4
+            }
+
+            fn read_absolute(bytes: &[u8], offset: usize, width: usize) -> usize {
+                <LittleEndian as Decoder>::read_absolute(bytes, offset, width)
+            }
+        }
+
+        let bytes = [0xE8u8, 0x04, 0x00, 0x00, 0x00];
+        let searcher = RelativeFinder::<WordDecoder>::new(0x1000, 4, 0x1000 + 5 + 16);
         assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x1000 + 5 + 4);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
+    }
+
+    #[test]
+    fn check_find_xref_sib() {
+        // mov eax, [rbx + rcx * 4 + 0x10]
+        let bytes = [0x8Bu8, 0x84, 0x8B, 0x10, 0x00, 0x00, 0x00];
+        let searcher = SibFinder::<LittleEndian>::new(0x1000, 0x20, 4, 0x1090);
+
+        assert_eq!(searcher.next(&bytes), Some(3));
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [3]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_sib_illegal_scale() {
+        SibFinder::<LittleEndian>::new(0, 0, 3, 0);
+    }
+
+    #[test]
+    fn check_progress() {
+        let mut bytes = [0x00u8; 64];
+        for offset in [0, 20, 40, 56] {
+            bytes[offset..offset + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        }
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+
+        let progress = searcher.all_with_progress(&bytes).collect::<Vec<_>>();
+        assert_eq!(progress.len(), 4);
+        assert_eq!(progress[0], (0, 0.0));
+        assert!(progress.windows(2).all(|pair| pair[0].1 < pair[1].1));
+        assert!(progress
+            .iter()
+            .all(|&(_, part)| (0.0..=1.0).contains(&part)));
+    }
+
+    #[test]
+    fn check_paged() {
+        let mut bytes = [0x00u8; 256];
+        for offset in [5, 60, 130] {
+            bytes[offset..offset + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        }
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+
+        // The reference at 60 straddles the boundary at 64
+        let paged = searcher.all_paged(&bytes, 64).collect::<Vec<_>>();
+        assert_eq!(paged, searcher.all(&bytes).collect::<Vec<_>>());
+        assert_eq!(paged, [5, 60, 130]);
+        assert_eq!(searcher.all_paged(&bytes, 3).collect::<Vec<_>>(), paged);
+        assert_eq!(
+            searcher.all_paged(&bytes, usize::MAX).collect::<Vec<_>>(),
+            paged
+        );
+        assert!(!searcher.may_contain(&bytes[140..]));
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_find_xref_preference() {
+        // Both a relative offset of 0x2000 from 0xFFFFFFFC and the absolute address 0x100002000
+        let bytes = 0x100002000usize.to_le_bytes();
+        let searcher = RelativeAndAbsoluteFinder::<LittleEndian>::new(0xFFFFFFFC, 4, 0x100002000);
+
+        assert_eq!(
+            searcher.match_kind(&bytes, 0),
+            Some(ReferenceKind::Relative)
+        );
+        let searcher = searcher.with_preference(Prefer::Absolute);
+        assert_eq!(
+            searcher.match_kind(&bytes, 0),
+            Some(ReferenceKind::Absolute)
+        );
+        let searcher = searcher.with_preference(Prefer::Relative);
+        assert_eq!(
+            searcher.match_kind(&bytes, 0),
+            Some(ReferenceKind::Relative)
+        );
+        assert_eq!(searcher.match_kind(&bytes, 1), None);
+    }
+
+    #[test]
+    fn check_per_cache_line() {
+        let mut bytes = [0x00u8; 64];
+        for offset in [1, 9, 40] {
+            bytes[offset..offset + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        }
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+
+        assert_eq!(
+            searcher.per_cache_line(&bytes, 16).collect::<Vec<_>>(),
+            [(0, 2), (2, 1)]
+        );
+        assert_eq!(
+            searcher.per_cache_line(&bytes, 64).collect::<Vec<_>>(),
+            [(0, 3)]
+        );
+    }
+
+    #[test]
+    fn check_patch_all() {
+        let mut bytes = vec![0x00u8; 32];
+        for offset in [2, 20] {
+            bytes[offset..offset + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        }
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+
+        let mut patched = Vec::new();
+        searcher.patch_all(&mut bytes, |offset, window| {
+            patched.push(offset);
+            window.copy_from_slice(&0x5678usize.to_le_bytes());
+        });
+        assert_eq!(patched, [2, 20]);
+        assert_eq!(searcher.next(&bytes), None);
+        assert_eq!(
+            AbsoluteFinder::<LittleEndian>::new(0x5678)
+                .all(&bytes)
+                .collect::<Vec<_>>(),
+            [2, 20]
+        );
+    }
+
+    #[test]
+    fn check_all_rev() {
+        let mut bytes = [0x00u8; 64];
+        for offset in [2, 20, 40, 50] {
+            bytes[offset..offset + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        }
+        let searcher = CountingFinder::new(AbsoluteFinder::<LittleEndian>::new(0x1234));
+
+        let mut forward = searcher.all(&bytes).collect::<Vec<_>>();
+        forward.reverse();
+        assert_eq!(searcher.all_rev(&bytes).collect::<Vec<_>>(), forward);
+
+        searcher.calls.set(0);
+        assert_eq!(
+            searcher.all_rev(&bytes).take(2).collect::<Vec<_>>(),
+            [50, 40]
+        );
+        assert_eq!(
+            searcher.calls.get(),
+            bytes.len() - size_of::<usize>() + 1 - 40
+        );
+        assert_eq!(
+            searcher.all_rev(&bytes[..size_of::<usize>() - 1]).next(),
+            None
+        );
+    }
+
+    #[test]
+    fn check_displacement_for() {
+        use relative_finder::{displacement_for, is_relative_match};
+
+        for target in [0x0, 0x1000, 0x10000000, 0x7FFFFFFF] {
+            let displacement = displacement_for(0x1000, 4, 3, target).unwrap();
+            assert!(is_relative_match(0x1003, 4, displacement as isize, target));
+        }
+
+        let mut bytes = [0xE8u8, 0x00, 0x00, 0x00, 0x00];
+        let displacement = displacement_for(0x1000, 4, 1, 0x2000).unwrap();
+        bytes[1..].copy_from_slice(&displacement.to_le_bytes());
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x2000);
+        assert_eq!(searcher.next(&bytes), Some(1));
+
+        // The next instruction wraps around the end of the address space
+        assert_eq!(displacement_for(usize::MAX, 4, 0, 0), Ok(-3));
+        let displacement = displacement_for(usize::MAX - 0xF, 4, 1, 0x10).unwrap();
+        assert!(is_relative_match(
+            usize::MAX - 0xE,
+            4,
+            displacement as isize,
+            0x10
+        ));
+        let displacement = displacement_for(0x10, 4, 1, usize::MAX - 0xF).unwrap();
+        assert!(is_relative_match(
+            0x11,
+            4,
+            displacement as isize,
+            usize::MAX - 0xF
+        ));
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_displacement_for_out_of_range() {
+        use relative_finder::{displacement_for, OutOfRange};
+
+        assert_eq!(displacement_for(0x1000, 4, 0, 0x100001000), Err(OutOfRange));
+    }
+
+    #[test]
+    fn check_find_xref_code_heuristic() {
+        // call 0x1010 ; at 0x1000
+        // add al, 0x06 ; at 0x1005, the immediate also resolves to 0x1010
+        let bytes = [0xE8u8, 0x0B, 0x00, 0x00, 0x00, 0x04, 0x06, 0x00, 0x00, 0x00];
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x1010);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1, 6]);
+
+        let searcher = CodeHeuristicRelativeFinder::<LittleEndian>::new(0x1000, 0x1010);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
+
+        // je 0x1010 ; at 0x1000
+        let bytes = [0x0Fu8, 0x84, 0x0A, 0x00, 0x00, 0x00];
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [2]);
+        assert_eq!(searcher.all(&bytes[1..]).count(), 0);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_find_xref_mixed_width() {
+        let mut bytes = [0x00u8; 32];
+        bytes[3..7].copy_from_slice(&0x12345678u32.to_le_bytes());
+        bytes[16..24].copy_from_slice(&0x1122334455667788u64.to_le_bytes());
+        let targets = [(0x12345678, 4), (0x1122334455667788, 8)];
+        let searcher = MixedWidthMultiFinder::<LittleEndian>::new(&targets);
+
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [3, 16]);
+        assert_eq!(searcher.match_at(&bytes, 3), Some((0x12345678, 4)));
+        assert_eq!(searcher.match_at(&bytes, 16), Some((0x1122334455667788, 8)));
+        assert_eq!(searcher.match_at(&bytes, 4), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_mixed_width_illegal_width() {
+        MixedWidthMultiFinder::<LittleEndian>::new(&[(0, size_of::<usize>() + 1)]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn check_clone_box() {
+        let bytes = [0x00u8, 0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF];
+        let boxed: Box<dyn CloneFinder> = Box::new(RelativeAndAbsoluteFinder::<LittleEndian>::new(
+            0,
+            4,
+            0x67452301 + 5,
+        ));
+        let cloned = boxed.clone();
+
+        assert_eq!(cloned.next(&bytes), boxed.next(&bytes));
+        assert_eq!(
+            cloned.all(&bytes).collect::<Vec<_>>(),
+            boxed.all(&bytes).collect::<Vec<_>>()
+        );
+        assert_eq!(cloned.all(&bytes).collect::<Vec<_>>(), [1]);
+    }
+
+    #[test]
+    fn check_aligned_word() {
+        let mut bytes = [0x00u8; 36];
+        for offset in [5, 26] {
+            bytes[offset..offset + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        }
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+
+        let words = searcher
+            .all_with_aligned_word(&bytes, 16)
+            .collect::<Vec<_>>();
+        assert_eq!(words.len(), 2);
+        for (offset, word) in words {
+            let start = word.as_ptr() as usize - bytes.as_ptr() as usize;
+            assert_eq!(start % 16, 0);
+            assert!((start..start + word.len()).contains(&offset));
+        }
+        assert_eq!(
+            searcher.all_with_aligned_word(&bytes, 16).last().unwrap().1,
+            &bytes[16..32]
+        );
+        assert_eq!(
+            searcher.all_with_aligned_word(&bytes, 24).last().unwrap().1,
+            &bytes[24..]
+        );
+    }
+
+    #[test]
+    fn check_satisfiable() {
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x2000);
+        assert!(searcher.is_satisfiable(0x100));
+        assert!(!searcher.is_satisfiable(3));
+
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x1004 + i32::MAX as usize);
+        assert!(searcher.is_satisfiable(4));
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x1005 + i32::MAX as usize);
+        assert!(!searcher.is_satisfiable(4));
+        assert!(searcher.is_satisfiable(5));
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_satisfiable_far_target() {
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x7FFF00000000);
+        assert!(!searcher.is_satisfiable(0x100000));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn check_find_all() {
+        let mut bytes = [0x00u8; 32];
+        for offset in [2, 10, 24] {
+            bytes[offset..offset + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        }
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+        assert_eq!(searcher.find_all(&bytes), [2, 10, 24]);
+        assert_eq!(
+            searcher.find_all_addresses(&bytes, 0x400000),
+            [0x400002, 0x40000A, 0x400018]
+        );
+        assert_eq!(
+            searcher.find_all_addresses(&bytes, usize::MAX - 9),
+            [usize::MAX - 7]
+        );
+        assert_eq!(searcher.find_all(&bytes[..8]), []);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn check_find_all_in() {
+        let mut bytes = [0x00u8; 32];
+        for offset in [2, 10, 24] {
+            bytes[offset..offset + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        }
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+
+        let mut scratch = Vec::new();
+        assert_eq!(searcher.find_all_in(&bytes, &mut scratch), 3);
+        assert_eq!(scratch, [2, 10, 24]);
+        let capacity = scratch.capacity();
+
+        assert_eq!(searcher.find_all_in(&bytes[8..], &mut scratch), 2);
+        assert_eq!(scratch, [2, 16]);
+        assert_eq!(scratch.capacity(), capacity);
+    }
+
+    #[test]
+    fn check_find_xref_leb128() {
+        use leb128_relative_finder::decode_leb128;
+
+        assert_eq!(
+            decode_leb128(&[0xE5, 0x8E, 0x26], 0, false),
+            Some((624485, 3))
+        );
+        assert_eq!(
+            decode_leb128(&[0xC0, 0xBB, 0x78], 0, true),
+            Some((-123456, 3))
+        );
+        assert_eq!(
+            decode_leb128(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F], 0, false),
+            Some((u32::MAX as isize, 5))
+        );
+        assert_eq!(
+            decode_leb128(&[0xFF, 0xFF, 0xFF, 0xFF, 0x1F], 0, false),
+            None
+        );
+        assert_eq!(decode_leb128(&[0x80, 0x80], 0, false), None);
+
+        // 624485 from 0x1000 + 1 + 3
+        let bytes = [0xFFu8, 0xE5, 0x8E, 0x26, 0xFF];
+        let searcher = Leb128RelativeFinder::new(0x1000, 0x1004 + 624485);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
+
+        // -0x100 from 0x1000 + 1 + 2
+        let bytes = [0xFFu8, 0x80, 0x7E];
+        let searcher = Leb128RelativeFinder::new_signed(0x1000, 0x1003 - 0x100);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
+    }
+
+    #[test]
+    fn check_find_xref_rel_lengths() {
+        // cmp dword ptr [rip + 0x10], 0x0 ; at 0x1000, the displacement is followed by 1 byte
+        let bytes = [0x83u8, 0x3D, 0x10, 0x00, 0x00, 0x00, 0x00];
+        let target = 0x1000 + 2 + 5 + 0x10;
+
+        assert_eq!(
+            RelativeFinder::<LittleEndian>::new(0x1000, 4, target).next(&bytes),
+            None
+        );
+
+        let searcher = RelativeFinder::<LittleEndian>::with_lengths(0x1000, &[4, 6, 5], target);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [2]);
+        assert_eq!(searcher.match_at(&bytes, 2), Some(5));
+        assert_eq!(searcher.match_at(&bytes, 1), None);
+
+        assert_eq!(
+            searcher.all_with_length(&bytes).collect::<Vec<_>>(),
+            [(2, 5)]
+        );
+
+        let searcher = RelativeFinder::<LittleEndian>::with_lengths(0x1000, &[4, 6], target);
+        assert_eq!(searcher.next(&bytes), None);
+        assert!(
+            !RelativeFinder::<LittleEndian>::with_lengths(0x1000, &[], target).is_satisfiable(7)
+        );
+    }
+
+    #[test]
+    fn check_prev_ending_before() {
+        let mut bytes = [0x00u8; 40];
+        for offset in [2, 12, 24] {
+            bytes[offset..offset + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        }
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+        let width = size_of::<usize>();
+
+        // The reference at 12 straddles the cursor
+        assert_eq!(searcher.prev_ending_before(&bytes, 12 + width - 1), Some(2));
+        assert_eq!(searcher.prev_ending_before(&bytes, 12 + width), Some(12));
+        assert_eq!(searcher.prev_ending_before(&bytes, 100), Some(24));
+        assert_eq!(searcher.prev_ending_before(&bytes, 2 + width - 1), None);
+        assert_eq!(searcher.prev_ending_before(&bytes, 0), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn check_write_matches() {
+        use byteorder::ReadBytesExt;
+        use std::io::Cursor;
+
+        let mut bytes = [0x00u8; 32];
+        for offset in [2, 10, 24] {
+            bytes[offset..offset + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        }
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+
+        let mut out = Vec::new();
+        searcher.write_matches(&bytes, &mut out).unwrap();
+        assert_eq!(out.len(), 4 * size_of::<u64>());
+
+        let mut input = Cursor::new(out);
+        let count = input.read_u64::<LittleEndian>().unwrap();
+        let matches = (0..count)
+            .map(|_| input.read_u64::<LittleEndian>().unwrap() as usize)
+            .collect::<Vec<_>>();
+        assert_eq!(matches, searcher.all(&bytes).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn check_find_xref_rel_section_end() {
+        // The section occupies 0x1000..0x2000, the table references 0x1800
+        let mut bytes = [0x00u8; 8];
+        bytes[4..].copy_from_slice(&(0x1800i32 - (0x2000 + 4 + 4)).to_le_bytes());
+        let searcher = RelativeFinder::<LittleEndian>::from_section_end(0x2000, 4, 0x1800);
+
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [4]);
+    }
+
+    #[test]
+    fn check_code_heuristic_edges() {
+        // call 0x1010 ; at 0x1000, the buffer ends with the displacement
+        let bytes = [0xE8u8, 0x0B, 0x00, 0x00, 0x00];
+        let searcher = CodeHeuristicRelativeFinder::<LittleEndian>::new(0x1000, 0x1010);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
+        for offset in [0, 2, 4, 5, 6, usize::MAX] {
+            assert!(!searcher.does_match(&bytes, offset));
+        }
+
+        // The opcode is cut off
+        let searcher = CodeHeuristicRelativeFinder::<LittleEndian>::new(0x1001, 0x1010);
+        assert!(!searcher.does_match(&bytes[1..], 0));
+
+        // jne 0x1010 ; at 0x1000, only the second opcode byte is part of the buffer
+        let bytes = [0x0Fu8, 0x85, 0x0A, 0x00, 0x00, 0x00];
+        let searcher = CodeHeuristicRelativeFinder::<LittleEndian>::new(0x1000, 0x1010);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [2]);
+        let searcher = CodeHeuristicRelativeFinder::<LittleEndian>::new(0x1001, 0x1010);
+        assert_eq!(searcher.all(&bytes[1..]).count(), 0);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_find_xref_combined_rev() {
+        let mut bytes = [0x00u8; 32];
+        bytes[2..10].copy_from_slice(&0x100002000u64.to_le_bytes());
+        // Relative references at 12 and in the last 4 bytes, where an absolute reference doesn't fit
+        bytes[12..16].copy_from_slice(&(0x2000i32 - 16).to_le_bytes());
+        bytes[28..32].copy_from_slice(&(0x2000i32 - 32).to_le_bytes());
+        let searcher = RelativeAndAbsoluteFinder::<LittleEndian>::new(0x100000000, 4, 0x100002000);
+
+        let mut forward = searcher.all(&bytes).collect::<Vec<_>>();
+        assert_eq!(forward, [2, 12, 28]);
+        forward.reverse();
+        assert_eq!(searcher.all_rev(&bytes).collect::<Vec<_>>(), forward);
+        assert_eq!(searcher.all_rev(&bytes).next(), Some(28));
+    }
+
+    #[test]
+    fn check_find_xref_range_stride() {
+        let mut bytes = [0x00u8; 32];
+        // Absolute reference to the third entry
+        bytes[2..2 + size_of::<usize>()].copy_from_slice(&0x4020usize.to_le_bytes());
+        // Relative reference to the first entry
+        bytes[16..20].copy_from_slice(&(0x4000i32 - 0x1014).to_le_bytes());
+        // Relative reference between two entries
+        bytes[24..28].copy_from_slice(&(0x4008i32 - 0x101C).to_le_bytes());
+        let searcher =
+            MultiTargetFinder::<LittleEndian>::from_range_stride(0x1000, 4, 0x4000, 0x4100, 0x10);
+
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [2, 16]);
+        assert_eq!(searcher.match_at(&bytes, 2), Some(0x4020));
+        assert_eq!(searcher.match_at(&bytes, 16), Some(0x4000));
+        assert_eq!(searcher.match_at(&bytes, 24), None);
+
+        let searcher =
+            MultiTargetFinder::<LittleEndian>::from_range_stride(0x1000, 4, 0x4000, 0x4020, 0x10);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [16]);
+    }
+
+    #[test]
+    fn check_all_spaced() {
+        let mut bytes = [0x00u8; 32];
+        for offset in [4, 8, 20] {
+            bytes[offset..offset + 4]
+                .copy_from_slice(&(0x2000 - 0x1004 - offset as i32).to_le_bytes());
+        }
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x2000);
+
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [4, 8, 20]);
+        assert_eq!(searcher.all_spaced(&bytes, 8).collect::<Vec<_>>(), [4, 20]);
+        assert_eq!(
+            searcher.all_spaced(&bytes, 4).collect::<Vec<_>>(),
+            [4, 8, 20]
+        );
+        assert_eq!(searcher.all_spaced(&bytes, 17).collect::<Vec<_>>(), [4]);
+    }
+
+    #[test]
+    fn check_find_xref_masked() {
+        let mut bytes = [0x00u8; 24];
+        bytes[2..2 + size_of::<usize>()].copy_from_slice(&0x12345678usize.to_le_bytes());
+        bytes[12..12 + size_of::<usize>()].copy_from_slice(&0x12340000usize.to_le_bytes());
+        let searcher = MaskedFinder::<LittleEndian>::new(0xFFFF0000, 0x12340000);
+
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [2, 12]);
+        assert_eq!(
+            MaskedFinder::<LittleEndian>::new(0xFF, 0x78)
+                .all(&bytes)
+                .collect::<Vec<_>>(),
+            [2]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn check_scan_builder() {
+        let mut bytes = [0x00u8; 32];
+        bytes[2..2 + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        bytes[20..24].copy_from_slice(&(0x2000i32 - 0x1018).to_le_bytes());
+        let scanner = ScanBuilder::<LittleEndian>::new()
+            .absolute(0x1234)
+            .labeled("absolute")
+            .relative(0x1000, 4, 0x2000)
+            .labeled("relative")
+            .masked(0xFFFF, 0x1234)
+            .build();
+
+        assert_eq!(
+            scanner.scan(&bytes).collect::<Vec<_>>(),
+            [(2, Some("absolute")), (2, None), (20, Some("relative"))]
+        );
+    }
+
+    #[test]
+    fn check_find_xref_indirect() {
+        let table = [0x1000, 0x2000, 0x3000];
+        let mut bytes = [0xFFu8; 16];
+        bytes[3..7].copy_from_slice(&2u32.to_le_bytes());
+        bytes[9..13].copy_from_slice(&1u32.to_le_bytes());
+        let searcher = IndirectFinder::<LittleEndian>::new(&table, 0x3000);
+
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [3]);
+        assert_eq!(searcher.match_at(&bytes, 3), Some(2));
+        assert_eq!(searcher.match_at(&bytes, 9), None);
+        assert_eq!(searcher.match_at(&bytes, 0), None);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn check_scan_dedup_by_address() {
+        let mut memory = [0x00u8; 48];
+        for offset in [0x04, 0x14, 0x28] {
+            memory[offset..offset + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        }
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+
+        let views = [(0x1000, &memory[..32]), (0x1010, &memory[0x10..])];
+        assert_eq!(
+            searcher.scan_dedup_by_address(&views),
+            [0x1004, 0x1014, 0x1028]
+        );
+    }
+
+    #[test]
+    fn check_all_with_next_gap() {
+        let mut bytes = [0x00u8; 32];
+        for offset in [4, 20, 24] {
+            bytes[offset..offset + 4]
+                .copy_from_slice(&(0x2000 - 0x1004 - offset as i32).to_le_bytes());
+        }
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x2000);
+
+        assert_eq!(
+            searcher.all_with_next_gap(&bytes).collect::<Vec<_>>(),
+            [(4, Some(16)), (20, Some(4)), (24, None)]
+        );
+        assert_eq!(searcher.all_with_next_gap(&[]).count(), 0);
+    }
+
+    #[test]
+    fn check_follow_chain() {
+        use absolute_finder::follow_chain;
+
+        let width = size_of::<usize>();
+        let mut bytes = [0x00u8; 64];
+        // 0x1000 -> 0x1010 -> 0x1020 -> 0xDEADBEEF
+        bytes[..width].copy_from_slice(&0x1010usize.to_le_bytes());
+        bytes[0x10..0x10 + width].copy_from_slice(&0x1020usize.to_le_bytes());
+        bytes[0x20..0x20 + width].copy_from_slice(&0xDEADBEEFusize.to_le_bytes());
+
+        assert!(follow_chain::<LittleEndian>(
+            &bytes, 0, 0x1000, 3, 0xDEADBEEF
+        ));
+        assert!(follow_chain::<LittleEndian>(
+            &bytes, 0x10, 0x1000, 2, 0xDEADBEEF
+        ));
+        assert!(follow_chain::<LittleEndian>(&bytes, 0, 0x1000, 2, 0x1020));
+        assert!(!follow_chain::<LittleEndian>(
+            &bytes, 0, 0x1000, 2, 0xDEADBEEF
+        ));
+        assert!(!follow_chain::<LittleEndian>(
+            &bytes, 0, 0x1000, 4, 0xDEADBEEF
+        ));
+        assert!(follow_chain::<LittleEndian>(
+            &bytes, 0x10, 0x1000, 0, 0x1010
+        ));
+    }
+
+    #[test]
+    fn check_count_skips_candidates() {
+        // Pseudo random bytes with a few planted references
+        let mut state = 0x2545F491u32;
+        let mut bytes = [0u8; 512];
+        for byte in &mut bytes {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            *byte = state as u8;
+        }
+        for offset in [7, 100, 300] {
+            bytes[offset..offset + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        }
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+
+        assert_eq!(searcher.count(&bytes), searcher.all(&bytes).count());
+        assert_eq!(searcher.count(&bytes), 3);
+        assert!(searcher.candidates(&bytes, 0).count() < bytes.len() / 16);
+    }
+
+    #[test]
+    fn check_find_xref_segmented() {
+        let mut bytes = [0x00u8; 32];
+        // Module at 0x10000 in 0..16, module at 0x50000 in 16..32, both reference 0x60000
+        bytes[4..8].copy_from_slice(&(0x60000 - 0x10008i32).to_le_bytes());
+        bytes[20..24].copy_from_slice(&(0x60000 - 0x50008i32).to_le_bytes());
+        let segments = [(0..16, 0x10000), (16..32, 0x50000)];
+        let searcher = SegmentedRelativeFinder::<LittleEndian>::new(&segments, 4, 0x60000);
+
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [4, 20]);
+
+        let segments = [(0..16, 0x10000)];
+        let searcher = SegmentedRelativeFinder::<LittleEndian>::new(&segments, 4, 0x60000);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [4]);
+
+        // A module loaded below its offset in the buffer, 20 is located at 0xC
+        bytes[20..24].copy_from_slice(&(0x100 - 0x10i32).to_le_bytes());
+        let segments = [(16..32, 0x8)];
+        let searcher = SegmentedRelativeFinder::<LittleEndian>::new(&segments, 4, 0x100);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [20]);
+    }
+
+    #[test]
+    fn check_find_xref_self_pointer() {
+        let width = size_of::<usize>();
+        let mut bytes = [0x00u8; 32];
+        // A list head at 0x1010, whose next pointer points to itself
+        bytes[0x10..0x10 + width].copy_from_slice(&0x1010usize.to_le_bytes());
+        bytes[0x18..0x18 + width].copy_from_slice(&0x1010usize.to_le_bytes());
+        let searcher = SelfPointerFinder::<LittleEndian>::new(0x1000);
+
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [0x10]);
+        assert_eq!(searcher.match_at(&bytes, 0x10), Some(0x1010));
+        assert_eq!(searcher.match_at(&bytes, 0x18), None);
+    }
+
+    #[test]
+    fn check_find_xref_modular() {
+        let width = size_of::<usize>();
+        let values = [24usize, 30, 45, 50, 59];
+        let mut bytes = [0x00u8; 40];
+        for (i, value) in values.iter().enumerate() {
+            bytes[i * width..(i + 1) * width].copy_from_slice(&value.to_le_bytes());
+        }
+        let searcher = ModularFinder::<LittleEndian>::new(7, 3);
+
+        let matches = (0..values.len())
+            .map(|i| i * width)
+            .filter(|&offset| searcher.does_match(&bytes, offset))
+            .collect::<Vec<_>>();
+        assert_eq!(matches, [0, 2 * width, 4 * width]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_modular_zero_modulus() {
+        ModularFinder::<LittleEndian>::new(0, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn check_coverage_index() {
+        let mut bytes = [0x00u8; 32];
+        for offset in [4, 8, 20] {
+            bytes[offset..offset + 4]
+                .copy_from_slice(&(0x2000 - 0x1004 - offset as i32).to_le_bytes());
+        }
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x2000);
+        let index = searcher.build_coverage_index(&bytes);
+
+        assert_eq!(index.contains(0), None);
+        assert_eq!(index.contains(4), Some(4));
+        assert_eq!(index.contains(7), Some(4));
+        assert_eq!(index.contains(8), Some(8));
+        assert_eq!(index.contains(12), None);
+        assert_eq!(index.contains(23), Some(20));
+        assert_eq!(index.contains(24), None);
+        assert_eq!(index.contains(usize::MAX), None);
+    }
+
+    #[test]
+    fn check_find_xref_modes() {
+        let mut bytes = [0x00u8; 32];
+        bytes[2..2 + size_of::<usize>()].copy_from_slice(&0x2000usize.to_le_bytes());
+        bytes[20..24].copy_from_slice(&(0x2000i32 - 0x1018).to_le_bytes());
+        let searcher = RelativeAndAbsoluteFinder::<LittleEndian>::new(0x1000, 4, 0x2000);
+
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [2, 20]);
+        assert_eq!(
+            searcher
+                .with_modes(true, true)
+                .all(&bytes)
+                .collect::<Vec<_>>(),
+            [2, 20]
+        );
+        assert_eq!(
+            searcher
+                .with_modes(true, false)
+                .all(&bytes)
+                .collect::<Vec<_>>(),
+            [20]
+        );
+        assert_eq!(
+            searcher
+                .with_modes(false, true)
+                .all(&bytes)
+                .collect::<Vec<_>>(),
+            [2]
+        );
+        assert_eq!(searcher.with_modes(false, false).next(&bytes), None);
+    }
+
+    #[test]
+    fn check_max_reach() {
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x2000);
+        assert_eq!(searcher.max_reach(3), None);
+        assert_eq!(searcher.max_reach(4), Some((0, 0x1004 + i32::MAX as usize)));
+        assert_eq!(
+            searcher.max_reach(0x104),
+            Some((0, 0x1104 + i32::MAX as usize))
+        );
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_max_reach_lengths() {
+        let searcher = RelativeFinder::<LittleEndian>::with_lengths(0x90000000, &[4, 6], 0x2000);
+        assert_eq!(
+            searcher.max_reach(0x10),
+            Some((
+                0x90000004 - 0x80000000,
+                (0x90000000 + 0xC + 6 + i32::MAX as u64) as usize
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn check_all_cancellable() {
+        /// Sets `cancel` once `does_match` reaches `at`
+        struct CancellingFinder<'a, F> {
+            inner: F,
+            cancel: &'a AtomicBool,
+            at: usize,
+        }
+
+        impl<F: XRefFinder> XRefFinder for CancellingFinder<'_, F> {
+            fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+                if offset == self.at {
+                    self.cancel.store(true, Ordering::Relaxed);
+                }
+                self.inner.does_match(bytes, offset)
+            }
+
+            fn match_width(&self) -> usize {
+                self.inner.match_width()
+            }
+        }
+
+        let mut bytes = vec![0x00u8; 4 * CANCEL_CHECK_INTERVAL];
+        let offsets = [
+            100,
+            CANCEL_CHECK_INTERVAL + 100,
+            2 * CANCEL_CHECK_INTERVAL + 100,
+        ];
+        for offset in offsets {
+            bytes[offset..offset + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        }
+        let cancel = AtomicBool::new(false);
+        let searcher = CancellingFinder {
+            inner: AbsoluteFinder::<LittleEndian>::new(0x1234),
+            cancel: &cancel,
+            at: CANCEL_CHECK_INTERVAL + 200,
+        };
+
+        assert_eq!(searcher.all_cancellable(&bytes, &cancel), offsets[..2]);
+        assert!(cancel.load(Ordering::Relaxed));
+        cancel.store(false, Ordering::Relaxed);
+        let searcher = CancellingFinder {
+            at: usize::MAX,
+            ..searcher
+        };
+        assert_eq!(searcher.all_cancellable(&bytes, &cancel), offsets);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn check_all_enumerated_total() {
+        let bytes = [
+            0x34, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x34, 0x12, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x34, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+
+        let (total, matches) = searcher.all_enumerated_total(&bytes);
+        assert_eq!(total, searcher.all(&bytes).count());
+        let matches: Vec<_> = matches.collect();
+        assert_eq!(matches.len(), total);
+        assert!(matches.iter().map(|&(index, _)| index).eq(0..total));
+        assert!(matches
+            .iter()
+            .map(|&(_, offset)| offset)
+            .eq(searcher.all(&bytes)));
+    }
+
+    #[test]
+    fn check_all_raw_bounded() {
+        let bytes = [
+            0x34, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x34, 0x12, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00,
+        ];
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+        let ptr = bytes.as_ptr();
+        let width = size_of::<usize>();
+
+        // SAFETY: Every bound is inside of `bytes`
+        unsafe {
+            let all = searcher.all_raw_bounded(ptr, bytes.len(), ptr.add(bytes.len()));
+            assert!(all.eq([ptr, ptr.add(9)]));
+            let all = searcher.all_raw_bounded(ptr, bytes.len(), ptr.add(9 + width - 1));
+            assert!(all.eq([ptr]));
+            let all = searcher.all_raw_bounded(ptr, 9 + width - 1, ptr.add(bytes.len()));
+            assert!(all.eq([ptr]));
+            assert_eq!(
+                searcher
+                    .all_raw_bounded(ptr.add(1), bytes.len() - 1, ptr)
+                    .count(),
+                0
+            );
+        }
+    }
+
+    #[test]
+    fn check_ascii_hex() {
+        let bytes = b"jmp deadbeef; mov 0xDEADBEEF; 1deadbeef; 0Xdeadbeef0; 0xDeAdBeEf";
+        let searcher = AsciiHexFinder::new(0xDEADBEEF);
+
+        assert_eq!(searcher.all(bytes).collect::<Vec<_>>(), [4, 18, 54]);
+        assert_eq!(searcher.match_at(bytes, 4), Some(8));
+        assert_eq!(searcher.match_at(bytes, 18), Some(10));
+        assert_eq!(
+            AsciiHexFinder::new(0).all(b"0 0x0 00").collect::<Vec<_>>(),
+            [0, 2]
+        );
+        assert_eq!(AsciiHexFinder::new(0).all(b"0x1234").count(), 0);
+        assert_eq!(AsciiHexFinder::new(0x1234).match_width(), 6);
+        assert_eq!(AsciiHexFinder::new(0x1234).min_bytes(), 4);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn check_all_timed() {
+        let mut bytes = vec![0x00u8; 16 * CANCEL_CHECK_INTERVAL];
+        bytes[100..100 + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+
+        let (matches, complete) = searcher.all_timed(&bytes, Duration::ZERO);
+        assert!(!complete);
+        assert!(matches.is_empty());
+        let (matches, complete) = searcher.all_timed(&bytes, Duration::from_secs(3600));
+        assert!(complete);
+        assert_eq!(matches, [100]);
+    }
+
+    #[test]
+    fn check_all_by_opcode_kind() {
+        // call 0x100A; jmp 0x100A; jne 0x100A
+        let bytes = [
+            0xE8, 0x05, 0x00, 0x00, 0x00, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x0F, 0x85, 0xFA, 0xFF,
+            0xFF, 0xFF,
+        ];
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x100A);
+
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1, 6, 12]);
+        let calls = searcher.all_by_opcode_kind(&bytes, &[RefMnemonic::Call]);
+        assert_eq!(calls.collect::<Vec<_>>(), [1]);
+        let kinds = [RefMnemonic::Jmp, RefMnemonic::Jcc];
+        let branches = searcher.all_by_opcode_kind(&bytes, &kinds);
+        assert_eq!(branches.collect::<Vec<_>>(), [6, 12]);
+    }
+
+    #[test]
+    fn check_prev_does_match() {
+        let bytes = [
+            0x00u8, 0x34, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x34, 0x12, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+
+        assert_eq!(searcher.prev(&bytes), Some(9));
+        assert!(searcher.does_match(&bytes, searcher.prev(&bytes).unwrap()));
+        assert_eq!(searcher.prev(&bytes), searcher.all(&bytes).last());
+    }
+
+    #[test]
+    fn check_next_from_prev_from() {
+        // jmp 0x100A; jmp 0x100A
+        let bytes = [0xE9, 0x05, 0x00, 0x00, 0x00, 0xE9, 0x00, 0x00, 0x00, 0x00];
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x100A);
+
+        assert_eq!(searcher.next_from(&bytes, 0), searcher.next(&bytes));
+        assert_eq!(searcher.next_from(&bytes, 2), Some(6));
+        assert_eq!(searcher.next_from(&bytes, 7), None);
+        assert_eq!(searcher.next_from(&bytes, bytes.len()), None);
+        assert_eq!(searcher.next_from(&bytes, usize::MAX), None);
+        assert_eq!(
+            searcher.prev_from(&bytes, bytes.len()),
+            searcher.prev(&bytes)
+        );
+        assert_eq!(searcher.prev_from(&bytes, 6), Some(1));
+        assert_eq!(searcher.prev_from(&bytes, 1), None);
+        assert_eq!(searcher.prev_from(&bytes, usize::MAX), Some(6));
+        // Reslicing shifts the offsets, but not the base address
+        assert_eq!(searcher.next(&bytes[2..]), None);
+    }
+
+    #[test]
+    fn check_with_prefixes() {
+        // jmp 0x1014 ; with operand-size override
+        let bytes = [0x66, 0xE9, 0x10, 0x00];
+        let searcher = RelativeFinder::<LittleEndian>::with_prefixes(0x1000, true, false, 0x1014);
+
+        assert_eq!(searcher.match_width(), 2);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [2]);
+        assert_eq!(searcher.match_at(&bytes, 2), Some(2));
+        let searcher = RelativeFinder::<LittleEndian>::with_prefixes(0x1000, false, true, 0x1014);
+        assert_eq!(searcher.match_width(), 4);
+        assert_eq!(searcher.next(&bytes), None);
+    }
+
+    #[test]
+    fn check_with_prefixes_truncate() {
+        // jmp 0x0004 ; at 0x1FFF0, ip wraps around at 16 bits
+        let bytes = [0x66, 0xE9, 0x10, 0x00];
+        let searcher = RelativeFinder::<LittleEndian>::with_prefixes(0x1FFF0, true, false, 0x4);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [2]);
+        assert!(searcher.is_satisfiable(bytes.len()));
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1FFF0, 2, 0x4)
+            .with_displacement_width(DisplacementWidth::Rel16);
+        assert_eq!(searcher.next(&bytes), None);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_with_prefixes_address_size() {
+        // jmp 0x14 ; at 0xFFFFFFEF, eip wraps around at 32 bits
+        let bytes = [0xE9u8, 0x20, 0x00, 0x00, 0x00];
+        let searcher = RelativeFinder::<LittleEndian>::with_prefixes(0xFFFFFFEF, false, true, 0x14);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
+        let searcher =
+            RelativeFinder::<LittleEndian>::with_prefixes(0xFFFFFFEF, false, false, 0x14);
+        assert_eq!(searcher.next(&bytes), None);
+        let searcher =
+            RelativeFinder::<LittleEndian>::with_prefixes(0xFFFFFFEF, false, false, 0x1_0000_0014);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
+    }
+
+    #[test]
+    fn check_all_with_remaining() {
+        let bytes = [
+            0x34, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x34, 0x12, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+        let width = size_of::<usize>();
+
+        let remaining = searcher.all_with_remaining(&bytes).collect::<Vec<_>>();
+        assert_eq!(
+            remaining,
+            [(0, bytes.len() - width), (10, bytes.len() - 10 - width)]
+        );
+    }
+
+    #[test]
+    fn check_absolute_fast_path() {
+        let target = 0xAB01usize;
+        let searcher = AbsoluteFinder::<LittleEndian>::new(target);
+        let mut state = 0x2545F4914F6CDD1Du64;
+        for len in 0..64 {
+            // Few distinct bytes, so that matches appear
+            let bytes: Vec<u8> = (0..len * 8)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    [0x00, 0x01, 0xAB][(state % 3) as usize]
+                })
+                .collect();
+            let naive: Vec<usize> = (0..bytes.len())
+                .filter(|&i| {
+                    bytes.len() - i >= size_of::<usize>()
+                        && LittleEndian::read_uint(&bytes[i..], size_of::<usize>()) as usize
+                            == target
+                })
+                .collect();
+
+            assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), naive);
+            assert_eq!(searcher.next(&bytes), naive.first().copied());
+            assert_eq!(
+                searcher.next_from(&bytes, 5),
+                naive.iter().find(|&&i| i >= 5).copied()
+            );
+            assert_eq!(searcher.count(&bytes), naive.len());
+        }
+    }
+
+    #[test]
+    fn check_next_until_byte() {
+        // nop; record boundary; jmp 0x100A
+        let bytes = [
+            0x90, 0x90, 0x90, 0x90, 0x90, 0xFF, 0xE9, 0xFF, 0xFF, 0xFF, 0xFF,
+        ];
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x100A);
+
+        assert_eq!(searcher.next(&bytes), Some(7));
+        assert_eq!(searcher.next_until_byte(&bytes, 0xFF), None);
+        assert_eq!(searcher.next_until_byte(&bytes, 0xCC), Some(7));
+    }
+
+    #[test]
+    #[cfg(all(feature = "alloc", target_pointer_width = "64"))]
+    fn check_multi_target_new() {
+        // call 0x100A; mov rax, 0x5000; jmp 0x100A
+        let bytes = [
+            0xE8, 0x05, 0x00, 0x00, 0x00, 0x48, 0xB8, 0x00, 0x50, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0xE9, 0xF6, 0xFF, 0xFF, 0xFF,
+        ];
+        let targets = (0..2000).map(|i| 0x5000 + i * 0x10).chain([0x100A, 0x100A]);
+        let searcher = MultiTargetFinder::<LittleEndian>::new(0x1000, 4, targets);
+
+        let matches = searcher.all_with_target(&bytes).collect::<Vec<_>>();
+        assert_eq!(matches, [(1, 0x100A), (7, 0x5000), (16, 0x100A)]);
+        assert!(searcher
+            .all(&bytes)
+            .eq(matches.iter().map(|&(offset, _)| offset)));
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_explain() {
+        // call 0x100A
+        let bytes = [0xE8, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let searcher = RelativeAndAbsoluteFinder::<LittleEndian>::new(0x1000, 4, 0x100A);
+
+        let explanation = searcher.explain(&bytes, 1);
+        assert_eq!(explanation.absolute_value, Some(5));
+        assert_eq!(explanation.displacement, Some(5));
+        assert_eq!(explanation.relative_address, Some(0x100A));
+        assert!(explanation.relative_matches);
+        assert!(!explanation.absolute_matches);
+
+        let explanation = searcher.explain(&bytes, 4);
+        assert_eq!(explanation.absolute_value, None);
+        assert_eq!(explanation.displacement, Some(0));
+        assert_eq!(explanation.relative_address, Some(0x1008));
+        assert!(!explanation.relative_matches);
+        assert_eq!(searcher.explain(&bytes, bytes.len()).displacement, None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn check_scan_file() {
+        let mut bytes = vec![0x00u8; 64];
+        bytes[13..13 + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        let path = std::env::temp_dir().join(format!("x86_xref_scan_file_{}", std::process::id()));
+        fs::write(&path, &bytes).unwrap();
+
+        let matches = scan_file(&path, &AbsoluteFinder::<LittleEndian>::new(0x1234));
+        fs::remove_file(&path).unwrap();
+        assert_eq!(matches.unwrap(), [13]);
+        assert!(scan_file(&path, &AbsoluteFinder::<LittleEndian>::new(0x1234)).is_err());
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_all_with_kind() {
+        // 0x100000010 as absolute value, 0x10 as relative offset
+        let bytes = [0x00, 0x10, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00];
+        let searcher = RelativeAndAbsoluteFinder::<LittleEndian>::new(0xFFFFFFFB, 4, 0x100000010);
+
+        let matches = searcher.all_with_kind(&bytes).collect::<Vec<_>>();
+        assert_eq!(
+            matches,
+            [(1, ReferenceKind::Relative), (1, ReferenceKind::Absolute)]
+        );
+        let searcher = searcher.with_preference(Prefer::Absolute);
+        let matches = searcher.all_with_kind(&bytes).collect::<Vec<_>>();
+        assert_eq!(
+            matches,
+            [(1, ReferenceKind::Absolute), (1, ReferenceKind::Relative)]
+        );
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn check_dump_matches() {
+        let mut bytes = *b"abc\x34\x12\0\0\0\0\0\0def";
+        bytes[3..3 + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+
+        #[cfg(target_pointer_width = "64")]
+        let expected = "00000003: 62 63 [34 12 00 00 00 00 00 00] 64 65  |bc4.......de|\n";
+        #[cfg(target_pointer_width = "32")]
+        let expected = "00000003: 62 63 [34 12 00 00] 00 00  |bc4.....|\n";
+        assert_eq!(searcher.dump_matches(&bytes, 2), expected);
+        assert_eq!(searcher.dump_matches(&bytes[..3], 2), "");
+    }
+
+    #[test]
+    fn check_new_range() {
+        // jmp 0x1012; mov eax, [0x1020]
+        let bytes = [
+            0xE9, 0x0D, 0x00, 0x00, 0x00, 0xA1, 0x20, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00,
+        ];
+        let searcher = RelativeFinder::<LittleEndian>::new_range(0x1000, 4, 0x1010, 0x1018);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1]);
+        let searcher = RelativeFinder::<LittleEndian>::new_range(0x1000, 4, 0x1013, 0x1018);
+        assert_eq!(searcher.next(&bytes), None);
+
+        let searcher = AbsoluteFinder::<LittleEndian>::new_range(0x1000, 0x1040);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [6]);
+        assert_eq!(searcher.count(&bytes), 1);
+        assert!(searcher.may_contain(&bytes));
+        let searcher = AbsoluteFinder::<LittleEndian>::new_range(0x1021, 0x1040);
+        assert_eq!(searcher.next(&bytes), None);
+
+        // A relative offset, which would lead the processor from 0x1 to 0x1000
+        let jump = (0x1000i32 - 0x5).to_le_bytes();
+        assert_eq!(
+            RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x1)
+                .destinations_from(&jump)
+                .collect::<Vec<_>>(),
+            [0]
+        );
+        for (lo, hi) in [(0x1020, 0x1020), (0x1040, 0x1000), (usize::MAX, 0)] {
+            assert_eq!(
+                AbsoluteFinder::<LittleEndian>::new_range(lo, hi).next(&bytes),
+                None
+            );
+            assert!(!AbsoluteFinder::<LittleEndian>::new_range(lo, hi).may_contain(&bytes));
+            let searcher = RelativeFinder::<LittleEndian>::new_range(0x1000, 4, lo, hi);
+            assert_eq!(searcher.next(&bytes), None);
+            assert!(!searcher.is_satisfiable(bytes.len()));
+            assert_eq!(searcher.destinations_from(&jump).count(), 0);
+        }
+        assert_eq!(
+            AbsoluteFinder::<LittleEndian>::new_range(0, usize::MAX).next(&bytes),
+            Some(0)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "macho")]
+    fn check_macho_rebase() {
+        use macho_rebase_finder::{rebases, Rebase, MAX_SEGMENT_OFFSET};
+
+        let width = size_of::<usize>();
+        let mut segment = vec![0x00u8; 4 * width];
+        segment[width..2 * width].copy_from_slice(&0x100usize.to_le_bytes());
+        segment[3 * width..].copy_from_slice(&0x100usize.to_le_bytes());
+        // REBASE_OPCODE_SET_TYPE_IMM(POINTER), REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB(2, width),
+        // REBASE_OPCODE_DO_REBASE_IMM_TIMES(1), REBASE_OPCODE_DONE
+        let opcodes = [0x11, 0x22, width as u8, 0x51, 0x00];
+        let searcher = MachORebaseFinder::<LittleEndian>::new(&opcodes, 2, 0x1000, 0x1100);
+
+        assert_eq!(searcher.all(&segment).collect::<Vec<_>>(), [width]);
+        assert_eq!(searcher.all_rebased(&segment).collect::<Vec<_>>(), [width]);
+        let searcher = MachORebaseFinder::<LittleEndian>::new(&opcodes, 1, 0x1000, 0x1100);
+        assert_eq!(searcher.next(&segment), None);
+
+        // REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB(1, 128), REBASE_OPCODE_DO_REBASE_ULEB_TIMES_SKIPPING_ULEB(2, 8)
+        let opcodes = [0x11, 0x21, 0x80, 0x01, 0x80, 0x02, 0x08];
+        let offsets = rebases(&opcodes).map(|rebase| rebase.segment_offset);
+        assert!(offsets.eq([128, 136 + width as u64]));
+        let rebase = Rebase {
+            kind: 1,
+            segment_index: 1,
+            segment_offset: 128,
+        };
+        assert_eq!(rebases(&opcodes).next(), Some(rebase));
+
+        // REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB(2, 0), REBASE_OPCODE_DO_REBASE_ULEB_TIMES(u64::MAX)
+        let opcodes = [
+            0x11, 0x22, 0x00, 0x60, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01,
+        ];
+        let searcher = MachORebaseFinder::<LittleEndian>::new(&opcodes, 2, 0x1000, 0x1100);
+        assert_eq!(
+            searcher.all(&segment).collect::<Vec<_>>(),
+            [width, 3 * width]
+        );
+        assert!(searcher.does_match(&segment, width));
+        assert_eq!(searcher.next_from(&segment, 2 * width), Some(3 * width));
+
+        // REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB(2, u32::MAX - 15), REBASE_OPCODE_DO_REBASE_ULEB_TIMES(u64::MAX)
+        let opcodes = [
+            0x11, 0x22, 0xF0, 0xFF, 0xFF, 0xFF, 0x0F, 0x60, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0x01,
+        ];
+        let offsets = rebases(&opcodes).map(|rebase| rebase.segment_offset);
+        assert!(offsets.eq((MAX_SEGMENT_OFFSET - 15..=MAX_SEGMENT_OFFSET).step_by(width)));
+    }
+
+    #[test]
+    fn check_with_alignment() {
+        let width = size_of::<usize>();
+        let mut bytes = vec![0x00u8; 4 * width];
+        bytes[3..3 + width].copy_from_slice(&0x1234usize.to_le_bytes());
+        bytes[2 * width..3 * width].copy_from_slice(&0x1234usize.to_le_bytes());
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [3, 2 * width]);
+        let aligned = searcher.with_alignment(0x1000, width);
+        assert_eq!(aligned.all(&bytes).collect::<Vec<_>>(), [2 * width]);
+        assert_eq!(aligned.next(&bytes), Some(2 * width));
+        assert!(!aligned.does_match(&bytes, 3));
+        // The alignment is relative to the base address
+        let shifted = searcher.with_alignment(0x1000 - 3, width);
+        assert_eq!(shifted.all(&bytes).collect::<Vec<_>>(), [3]);
+        assert!(searcher
+            .with_alignment(0x1000, 1)
+            .all(&bytes)
+            .eq(searcher.all(&bytes)));
+
+        let range = AbsoluteFinder::<LittleEndian>::new_range(0x1000, 0x2000);
+        assert_eq!(range.all(&bytes).collect::<Vec<_>>(), [3, 2 * width]);
+        let aligned = range.with_alignment(0x1000 - 3, width);
+        assert_eq!(aligned.all(&bytes).collect::<Vec<_>>(), [3]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_with_alignment_zero() {
+        AbsoluteFinder::<LittleEndian>::new(0x1234).with_alignment(0, 0);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_scan_sections() {
+        // .text: call 0x2000 ; at 0x1000
+        let text = [0xE8u8, 0xFB, 0x0F, 0x00, 0x00];
+        // .data: dq 0x2000, dq 0x2000 ; at 0x3000
+        let mut data = [0u8; 16];
+        data[..8].copy_from_slice(&0x2000usize.to_le_bytes());
+        data[8..].copy_from_slice(&0x2000usize.to_le_bytes());
+        let sections = [
+            Section {
+                index: 1,
+                address: 0x1000,
+                data: &text,
+            },
+            Section {
+                index: 3,
+                address: 0x3000,
+                data: &data,
+            },
+        ];
+        assert_eq!(
+            scan_sections(sections, Endianness::Little, 4, PointerWidth::Ptr64, 0x2000)
+                .collect::<Vec<_>>(),
+            [(1, 0x1001), (3, 0x3000), (3, 0x3008)]
+        );
+
+        let mut data = [0u8; 8];
+        data.copy_from_slice(&0x2000usize.to_be_bytes());
+        let section = Section {
+            index: 0,
+            address: 0x3000,
+            data: &data,
+        };
+        assert_eq!(
+            scan_sections([section], Endianness::Big, 4, PointerWidth::Ptr64, 0x2000)
+                .collect::<Vec<_>>(),
+            [(0, 0x3000)]
+        );
+        assert_eq!(
+            scan_sections(
+                [section],
+                Endianness::Little,
+                4,
+                PointerWidth::Ptr64,
+                0x2000
+            )
+            .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn check_scan_sections_32_bit() {
+        // .data: dd 0x2000, dd 0x2000 ; at 0x3000
+        let mut data = [0u8; 8];
+        data[..4].copy_from_slice(&0x2000u32.to_le_bytes());
+        data[4..].copy_from_slice(&0x2000u32.to_le_bytes());
+        let section = Section {
+            index: 2,
+            address: 0x3000,
+            data: &data,
+        };
+        assert_eq!(
+            scan_sections(
+                [section],
+                Endianness::Little,
+                4,
+                PointerWidth::Ptr32,
+                0x2000
+            )
+            .collect::<Vec<_>>(),
+            [(2, 0x3000), (2, 0x3004)]
+        );
+    }
+
+    #[test]
+    fn check_relative_extractor() {
+        // call 0x1010; jmp 0x1000; mov eax, 0x10 ; at 0x1000
+        let bytes = [
+            0xE8, 0x0B, 0x00, 0x00, 0x00, 0xE9, 0xF6, 0xFF, 0xFF, 0xFF, 0xB8, 0x10, 0x00, 0x00,
+            0x00,
+        ];
+        let extractor = RelativeExtractor::<LittleEndian>::new(0x1000, 4);
+        let extracted = extractor.extract(&bytes).collect::<Vec<_>>();
+        assert!(extracted.len() > 2);
+        for &(offset, target) in &extracted {
+            assert!(
+                RelativeFinder::<LittleEndian>::new(0x1000, 4, target).does_match(&bytes, offset)
+            );
+        }
+
+        let calls = extractor.with_opcodes(&[RefMnemonic::Call, RefMnemonic::Jmp]);
+        assert_eq!(
+            calls.extract(&bytes).collect::<Vec<_>>(),
+            [(1, 0x1010), (6, 0x1000)]
+        );
+
+        // Resolving below 0 wraps around, so it is skipped
+        let extractor = RelativeExtractor::<LittleEndian>::new(0, 3);
+        assert_eq!(extractor.resolve_at(&bytes, 6), None);
+        assert_eq!(extractor.resolve_at(&bytes, 1), Some(0xF));
+        assert_eq!(extractor.resolve_at(&bytes, 12), None);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_find_xref_abs_sign_extended() {
+        let target = 0xFFFFFFFF80001000;
+        // mov rax, 0xFFFFFFFF80001000 (imm32); movabs rax, 0xFFFFFFFF80001000
+        let mut bytes = [
+            0x48u8, 0xC7, 0xC0, 0x00, 0x10, 0x00, 0x80, 0x48, 0xB8, 0, 0, 0, 0, 0, 0, 0, 0,
+        ];
+        bytes[9..].copy_from_slice(&(target as u64).to_le_bytes());
+
+        let searcher = AbsoluteFinder::<LittleEndian>::new(target);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [9]);
+        let searcher = searcher.with_sign_extended_imm32();
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [3, 9]);
+        assert_eq!(searcher.min_bytes(), 4);
+        assert!(searcher.does_match(&bytes[..7], 3));
+
+        // The immediate isn't sign-extended to a low address
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x80001000).with_sign_extended_imm32();
+        assert_eq!(searcher.next(&bytes), None);
+
+        let searcher =
+            AbsoluteFinder::<byteorder::BigEndian>::new(target).with_sign_extended_imm32();
+        assert_eq!(searcher.next(&[0xC0, 0x80, 0x00, 0x10, 0x00]), Some(1));
+        assert!(!searcher.may_contain(&[0xC0, 0x00, 0x10, 0x00]));
+    }
+
+    #[test]
+    fn check_find_xref_abs_real_mode() {
+        // jmp far F000:FFF0; dw 0xFFF0
+        let bytes = [0xEAu8, 0xF0, 0xFF, 0x00, 0xF0, 0xF0, 0xFF];
+
+        let near =
+            AbsoluteFinder::<LittleEndian>::new(0xFFF0).with_pointer_width(PointerWidth::Ptr16);
+        assert_eq!(near.match_width(), 2);
+        assert_eq!(near.all(&bytes).collect::<Vec<_>>(), [1, 5]);
+        let near =
+            AbsoluteFinder::<LittleEndian>::new(0x1FFF0).with_pointer_width(PointerWidth::Ptr16);
+        assert_eq!(near.next(&bytes), None);
+
+        let far =
+            AbsoluteFinder::<LittleEndian>::new(0xFFFF0).with_pointer_width(PointerWidth::Far16);
+        assert_eq!(far.match_width(), 4);
+        assert_eq!(far.all(&bytes).collect::<Vec<_>>(), [1]);
+        assert!(!far.does_match(&bytes, 5));
+        // FFFF:0000 addresses the same linear address
+        assert_eq!(far.next(&[0x00, 0x00, 0xFF, 0xFF]), Some(0));
+        let far = AbsoluteFinder::<LittleEndian>::new_range(0xF0000, 0x100000)
+            .with_pointer_width(PointerWidth::Far16);
+        // F0F0:00FF at 2 addresses 0xF0FFF
+        assert_eq!(far.all(&bytes).collect::<Vec<_>>(), [1, 2]);
+        let far =
+            AbsoluteFinder::<LittleEndian>::new(0xFFFF0).with_pointer_width(PointerWidth::Ptr32);
+        assert_eq!(far.next(&bytes), None);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_find_xref_abs_pointer_width() {
+        // mov eax, 0x401000; mov ecx, 0x401000
+        let bytes = [0xB8u8, 0x00, 0x10, 0x40, 0x00, 0xB9, 0x00, 0x10, 0x40, 0x00];
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x401000);
+        assert_eq!(searcher.next(&bytes), None);
+
+        let searcher = searcher.with_pointer_width(PointerWidth::Ptr32);
+        assert_eq!(searcher.match_width(), 4);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1, 6]);
+        assert!(!searcher.does_match(&bytes, 7));
+
+        let range = AbsoluteFinder::<LittleEndian>::new_range(0x400000, 0x500000)
+            .with_pointer_width(PointerWidth::Ptr32);
+        assert_eq!(range.all(&bytes).collect::<Vec<_>>(), [1, 6]);
+
+        let searcher = AbsoluteFinder::<byteorder::BigEndian>::new(0x401000)
+            .with_pointer_width(PointerWidth::Ptr32);
+        assert_eq!(searcher.next(&[0x00, 0x40, 0x10, 0x00]), Some(0));
+
+        // The value is zero-extended, so it can't reach past u32::MAX
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1_0040_1000)
+            .with_pointer_width(PointerWidth::Ptr32);
+        assert_eq!(searcher.next(&bytes), None);
+        assert_eq!(
+            AbsoluteFinder::<LittleEndian>::new(0x401000)
+                .with_pointer_width(PointerWidth::Ptr64)
+                .next(&bytes),
+            None
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "alloc", target_pointer_width = "64"))]
+    fn check_streaming_finder() {
+        fn check<F: XRefFinder>(finder_at: impl Fn(usize) -> F, bytes: &[u8]) {
+            let expected: Vec<usize> = finder_at(0x1000).all(bytes).collect();
+            assert!(!expected.is_empty());
+            for split in 0..=bytes.len() {
+                let mut streaming = StreamingFinder::new(0x1000, &finder_at);
+                let mut found = streaming.feed(&bytes[..split]);
+                found.extend(streaming.feed(&bytes[split..]));
+                found.extend(streaming.finish());
+                assert_eq!(found, expected, "split at {split}");
+            }
+            for chunk_size in 1..=bytes.len() {
+                let mut streaming = StreamingFinder::new(0x1000, &finder_at);
+                let mut found: Vec<usize> = bytes
+                    .chunks(chunk_size)
+                    .flat_map(|chunk| streaming.feed(chunk))
+                    .collect();
+                found.extend(streaming.finish());
+                assert_eq!(found, expected, "chunks of {chunk_size}");
+            }
+        }
+
+        let target = 0x1040usize;
+        let mut bytes = [0x90u8; 0x30];
+        // call target ; at 0x1003
+        bytes[3] = 0xE8;
+        bytes[4..8].copy_from_slice(&(target as i32 - 0x1008).to_le_bytes());
+        bytes[0x10..0x18].copy_from_slice(&target.to_le_bytes());
+        bytes[0x1B..0x23].copy_from_slice(&target.to_le_bytes());
+        // jne target ; at 0x1024
+        bytes[0x24..0x26].copy_from_slice(&[0x0F, 0x85]);
+        bytes[0x26..0x2A].copy_from_slice(&(target as i32 - 0x102A).to_le_bytes());
+        // jmp target ; at the very end
+        bytes[0x2B] = 0xE9;
+        bytes[0x2C..0x30].copy_from_slice(&(target as i32 - 0x1030).to_le_bytes());
+
+        check(
+            |base| RelativeAndAbsoluteFinder::<LittleEndian>::new(base, 4, target),
+            &bytes,
+        );
+        check(
+            |base| RelativeFinder::<LittleEndian>::new(base, 4, target),
+            &bytes,
+        );
+        check(
+            |base| AbsoluteFinder::<LittleEndian>::new(target).with_alignment(base, 8),
+            &bytes,
+        );
+        // These inspect the opcode before the relative offset
+        check(
+            |base| {
+                RelativeFinder::<LittleEndian>::new(base, 4, target).with_opcodes(&[
+                    RefMnemonic::Call,
+                    RefMnemonic::Jcc,
+                    RefMnemonic::Jmp,
+                ])
+            },
+            &bytes,
+        );
+        check(
+            |base| CodeHeuristicRelativeFinder::<LittleEndian>::new(base, target),
+            &bytes,
+        );
+        // This inspects the byte after a prefixed text
+        check(
+            |_| AsciiHexFinder::new(target),
+            b"a 0x1040 b 0x10405 c 1040 d 0x1040",
+        );
+        let mut streaming = StreamingFinder::new(0x1000, |_| AsciiHexFinder::new(target));
+        let mut found = streaming.feed(b"0x1040");
+        found.extend(streaming.feed(b"5"));
+        found.extend(streaming.finish());
+        assert!(found.is_empty());
+
+        // call 0x100C ; at 0x1007, split in its relative offset
+        let mut call = [0u8; 12];
+        call[..7].fill(0x90);
+        call[7] = 0xE8;
+        let finder_at = |base| {
+            RelativeFinder::<LittleEndian>::new(base, 4, 0x100C).with_opcodes(&[RefMnemonic::Call])
+        };
+        let mut streaming = StreamingFinder::new(0x1000, finder_at);
+        let mut found = streaming.feed(&call[..11]);
+        found.extend(streaming.feed(&call[11..]));
+        found.extend(streaming.finish());
+        assert_eq!(found, [8]);
+        assert_eq!(
+            AbsoluteFinder::<LittleEndian>::new(target)
+                .with_alignment(0x1000, 8)
+                .all(&bytes)
+                .collect::<Vec<_>>(),
+            [0x10]
+        );
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_finder_builder() {
+        // call 0x100A; mov rax, 0x100A
+        let bytes = [
+            0xE8, 0x05, 0x00, 0x00, 0x00, 0x48, 0xB8, 0x0A, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00,
+        ];
+        let builder = FinderBuilder::<LittleEndian>::new().target(0x100A);
+
+        let finder = builder.build().unwrap();
+        assert!(matches!(finder, BuiltFinder::Absolute(_)));
+        assert_eq!(finder.all(&bytes).collect::<Vec<_>>(), [7]);
+        let finder = builder
+            .base_address(0x1000)
+            .alignment(2)
+            .pointer_width(PointerWidth::Ptr32)
+            .build()
+            .unwrap();
+        assert_eq!(finder.match_width(), 4);
+        assert_eq!(finder.next(&bytes), None);
+
+        let finder = builder
+            .base_address(0x1000)
+            .instruction_length(4)
+            .opcodes(&[RefMnemonic::Call])
+            .build()
+            .unwrap();
+        assert!(matches!(finder, BuiltFinder::Relative(_)));
+        assert_eq!(finder.all(&bytes).collect::<Vec<_>>(), [1]);
+        let finder = builder
+            .base_address(0x1000)
+            .instruction_lengths(&[5, 4])
+            .build()
+            .unwrap();
+        assert_eq!(finder.all(&bytes).collect::<Vec<_>>(), [1]);
+
+        let finder = builder
+            .base_address(0x1000)
+            .relative_and_absolute(Prefer::Absolute)
+            .build()
+            .unwrap();
+        assert!(matches!(finder, BuiltFinder::RelativeAndAbsolute(_)));
+        assert_eq!(finder.all(&bytes).collect::<Vec<_>>(), [1, 7]);
+
+        let error = |builder: FinderBuilder<LittleEndian>| builder.build().unwrap_err();
+        assert_eq!(
+            error(FinderBuilder::new().base_address(0x1000)),
+            BuildError::MissingTarget
+        );
+        assert_eq!(
+            error(builder.instruction_length(4)),
+            BuildError::MissingBaseAddress
+        );
+        assert_eq!(error(builder.alignment(0)), BuildError::ZeroAlignment);
+        assert_eq!(
+            error(builder.base_address(0x1000).instruction_lengths(&[4, 16])),
+            BuildError::InvalidInstructionLength
+        );
+        assert_eq!(
+            error(builder.base_address(0x1000).opcodes(&[]).alignment(8)),
+            BuildError::ConflictingOptions
+        );
+        assert_eq!(
+            error(
+                builder
+                    .base_address(0x1000)
+                    .instruction_lengths(&[4, 5])
+                    .relative_and_absolute(Prefer::Relative)
+            ),
+            BuildError::ConflictingOptions
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", target_pointer_width = "64"))]
+    fn check_par_all() {
+        let mut bytes = vec![0x00u8; 3 * CANCEL_CHECK_INTERVAL + 5];
+        // References at the start, straddling a range boundary and at the very end
+        let offsets = [1, CANCEL_CHECK_INTERVAL - 3, bytes.len() - 8];
+        for offset in offsets {
+            bytes[offset..offset + 8].copy_from_slice(&0x100Ausize.to_le_bytes());
+        }
+        // call 0x100A ; at 0x1000 + 2 * CANCEL_CHECK_INTERVAL - 2
+        let call = 2 * CANCEL_CHECK_INTERVAL - 2;
+        bytes[call..call + 4].copy_from_slice(&(0x100A - (0x1000 + call as i32 + 4)).to_le_bytes());
+
+        let absolute = AbsoluteFinder::<LittleEndian>::new(0x100A);
+        assert_eq!(absolute.par_all(&bytes), offsets);
+        let both = RelativeAndAbsoluteFinder::<LittleEndian>::new(0x1000, 4, 0x100A);
+        assert_eq!(both.par_all(&bytes), both.all(&bytes).collect::<Vec<_>>());
+        assert!(both.par_all(&bytes).contains(&call));
+        assert_eq!(absolute.par_all(&[]), []);
+    }
+
+    #[test]
+    fn check_matches() {
+        struct Scan<'a> {
+            matches: Matches<'a, AbsoluteFinder<LittleEndian>>,
+        }
+
+        let mut bytes = [0x00u8; 64];
+        for offset in [2, 20, 40, 50] {
+            bytes[offset..offset + size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+        }
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+        let mut scan = Scan {
+            matches: searcher.matches(&bytes),
+        };
+        assert_eq!(scan.matches.next(), Some(2));
+        assert_eq!(scan.matches.position(), 3);
+        assert_eq!(scan.matches.next_back(), Some(50));
+        assert_eq!(scan.matches.clone().collect::<Vec<_>>(), [20, 40]);
+        assert_eq!(scan.matches.next_back(), Some(40));
+        assert_eq!(scan.matches.next(), Some(20));
+        assert_eq!(scan.matches.next(), None);
+        assert_eq!(scan.matches.next_back(), None);
+
+        assert!(searcher.matches(&bytes).eq(searcher.all(&bytes)));
+        assert!(searcher.matches(&bytes).rev().eq(searcher.all_rev(&bytes)));
+
+        // call 0x1010 ; at 0x1000, resuming keeps the location of the offset
+        let bytes = [0xE8u8, 0x0B, 0x00, 0x00, 0x00, 0xE8, 0x06, 0x00, 0x00, 0x00];
+        let searcher: &dyn XRefFinder = &RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x1010);
+        assert_eq!(Matches::new(searcher, &bytes).collect::<Vec<_>>(), [1, 6]);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_generic_trait_methods() {
+        fn count_refs<F: XRefFinder>(f: &F, b: &[u8]) -> usize {
+            let mut visited = 0;
+            f.for_each_match(b, |offset| {
+                assert!(f.does_match(b, offset));
+                assert!(b.len() - offset >= f.min_bytes());
+                visited += 1;
+            });
+            assert_eq!(f.any(b), visited > 0);
+            assert_eq!(f.next_from(b, 0), f.next(b));
+            assert!(f.min_bytes() <= f.match_width());
+            assert_eq!(f.count(b), visited);
+            f.count(b)
+        }
+
+        // call 0x100A; mov rax, 0x100A
+        let bytes = [
+            0xE8, 0x05, 0x00, 0x00, 0x00, 0x48, 0xB8, 0x0A, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00,
+        ];
+        assert_eq!(
+            count_refs(&AbsoluteFinder::<LittleEndian>::new(0x100A), &bytes),
+            1
+        );
+        assert_eq!(
+            count_refs(
+                &RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x100A),
+                &bytes
+            ),
+            1
+        );
+        let searcher = RelativeAndAbsoluteFinder::<LittleEndian>::new(0x1000, 4, 0x100A);
+        assert_eq!(searcher.min_bytes(), size_of::<i32>());
+        assert_eq!(count_refs(&searcher, &bytes), 2);
+        assert_eq!(count_refs(&&searcher, &bytes), 2);
+        assert_eq!(
+            count_refs(
+                &CodeHeuristicRelativeFinder::<LittleEndian>::new(0x1000, 0x100A),
+                &bytes
+            ),
+            1
+        );
+        assert_eq!(count_refs(&AsciiHexFinder::new(0x100A), b"0x100a"), 1);
+        assert_eq!(
+            count_refs(&MaskedFinder::<LittleEndian>::new(!0xFF, 0x1000), &bytes),
+            1
+        );
+        let searcher =
+            MultiTargetFinder::<LittleEndian>::from_range_stride(0x1000, 4, 0x1000, 0x1010, 10);
+        assert_eq!(count_refs(&searcher, &bytes), 2);
+    }
+
+    #[test]
+    fn check_displacement_width() {
+        // nop; jmp 0x1000 (rel8); jmp 0x1000 (rel16)
+        let bytes = [0x90, 0xEB, 0xFD, 0x66, 0xE9, 0xF9, 0xFF];
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 1, 0x1000)
+            .with_displacement_width(DisplacementWidth::Rel8);
+
+        assert_eq!(searcher.match_width(), 1);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [2]);
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 2, 0x1000)
+            .with_displacement_width(DisplacementWidth::Rel16);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [5]);
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x1000);
+        assert_eq!(searcher.match_width(), DisplacementWidth::default().bytes());
+        assert_eq!(searcher.next(&bytes), None);
+    }
+
+    #[test]
+    fn check_all_credible() {
+        /// Matches at fixed offsets
+        struct AtOffsets(&'static [usize]);
+
+        impl XRefFinder for AtOffsets {
+            fn does_match(&self, _bytes: &[u8], offset: usize) -> bool {
+                self.0.contains(&offset)
+            }
+
+            fn match_width(&self) -> usize {
+                size_of::<i32>()
+            }
+        }
+
+        let bytes = [0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let scores = AtOffsets(&[1, 5, 6])
+            .all_credible(&bytes)
+            .collect::<Vec<_>>();
+
+        assert_eq!(scores, [(1, 3), (5, 2), (6, 1)]);
+    }
+
+    #[test]
+    fn check_with_opcodes() {
+        // call 0x100A; jmp 0x100A; jne 0x100A; a coincidental displacement to 0x100A
+        let bytes = [
+            0xE8, 0x05, 0x00, 0x00, 0x00, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x0F, 0x85, 0xFA, 0xFF,
+            0xFF, 0xFF, 0x90, 0xF5, 0xFF, 0xFF, 0xFF,
+        ];
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x100A);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [1, 6, 12, 17]);
+
+        let calls = searcher.with_opcodes(&[RefMnemonic::Call]);
+        assert_eq!(calls.all(&bytes).collect::<Vec<_>>(), [1]);
+        let branches =
+            searcher.with_opcodes(&[RefMnemonic::Call, RefMnemonic::Jmp, RefMnemonic::Jcc]);
+        assert_eq!(branches.all(&bytes).collect::<Vec<_>>(), [1, 6, 12]);
+        assert_eq!(searcher.with_opcodes(&[]).next(&bytes), None);
+        // No opcode fits before the start of the bytes
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1001, 4, 0x100A);
+        assert_eq!(searcher.next(&bytes[1..]), Some(0));
+        assert_eq!(
+            searcher
+                .with_opcodes(&[RefMnemonic::Call])
+                .next(&bytes[1..]),
+            None
+        );
+    }
+
+    #[test]
+    fn check_all_dual() {
+        let bytes = [
+            0x34, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x34, 0x12, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00,
+        ];
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x1234);
+
+        let matches = searcher
+            .all_dual(&bytes, 0x400, 0x401000)
+            .collect::<Vec<_>>();
+        assert_eq!(matches, [(0x400, 0x401000), (0x409, 0x401009)]);
+
+        // The second reference would leave the address space
+        let matches = searcher
+            .all_dual(&bytes, 0x400, usize::MAX - 0x8)
+            .collect::<Vec<_>>();
+        assert_eq!(matches, [(0x400, usize::MAX - 0x8)]);
+        let matches = searcher
+            .all_dual(&bytes, usize::MAX - 0x8, 0x401000)
+            .collect::<Vec<_>>();
+        assert_eq!(matches, [(usize::MAX - 0x8, 0x401000)]);
+    }
+
+    #[test]
+    fn check_runtime_endianness() {
+        let mut bytes = [0x00u8; 18];
+        bytes[8..10].copy_from_slice(&[0x12, 0x34]);
+        let width = size_of::<usize>();
+
+        let cases = [
+            (Endianness::Little, 0x3412, 8),
+            (Endianness::Big, 0x1234, 10 - width),
+        ];
+        for (endianness, target, offset) in cases {
+            let searcher = AbsoluteFinder::new_with_endian(target, endianness);
+            assert_eq!(searcher.endianness(), endianness);
+            assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [offset]);
+            let naive = (0..bytes.len()).filter(|&i| searcher.does_match(&bytes, i));
+            assert!(searcher.all(&bytes).eq(naive));
+        }
+
+        let target = 0x1000 + 6 + 4 + 0x1234;
+        let searcher = RelativeFinder::new_with_endian(0x1000, 4, target, Endianness::Big);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [6]);
+        let target = 0x1000 + 8 + 4 + 0x3412;
+        let searcher = RelativeFinder::new_with_endian(0x1000, 4, target, Endianness::Little);
+        assert_eq!(searcher.all(&bytes).collect::<Vec<_>>(), [8]);
+        assert_eq!(
+            Endianness::native() == Endianness::Little,
+            cfg!(target_endian = "little")
+        );
+    }
+
+    /// Checks that the traversal methods of `finder` agree with each other on every prefix of `bytes`,
+    /// which ends up to 1 byte before or after a multiple of its width
+    fn check_traversal_invariants<F: XRefFinder>(finder: &F, bytes: &[u8]) {
+        let width = finder.match_width();
+        let lengths = (0..=bytes.len() / width.max(1) + 1)
+            .flat_map(|n| [n * width, n * width + 1, (n * width).saturating_sub(1)])
+            .filter(|&len| len <= bytes.len());
+        for len in lengths.chain([bytes.len()]) {
+            let bytes = &bytes[..len];
+            let all = finder.all(bytes).collect::<Vec<_>>();
+            assert_eq!(all.len(), finder.count(bytes), "count of {len} bytes");
+            assert!(
+                all.iter().all(|&offset| len - offset >= finder.min_bytes()),
+                "min_bytes of {len} bytes"
+            );
+            let dynamic: &dyn XRefFinder = &finder;
+            assert_eq!(all.len(), dynamic.count(bytes), "dyn count of {len} bytes");
+            assert_eq!(
+                all.first().copied(),
+                finder.next(bytes),
+                "next of {len} bytes"
+            );
+            assert_eq!(
+                all.last().copied(),
+                finder.prev(bytes),
+                "prev of {len} bytes"
+            );
+            assert!(finder.all_rev(bytes).eq(all.iter().rev().copied()));
+            assert_eq!(finder.any(bytes), !all.is_empty());
+            assert!(all.is_empty() || finder.may_contain(bytes));
+            for &offset in &all {
+                assert_eq!(finder.next_from(bytes, offset), Some(offset));
+                assert!(finder.does_match(bytes, offset));
+            }
+        }
+    }
+
+    #[test]
+    fn check_traversal_invariants_all_finders() {
+        let mut bytes = vec![
+            // call 0x100A
+            0xE8, 0x05, 0x00, 0x00, 0x00, //
+            // 0x100A
+            0x0A, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let mut state = 0x9E3779B97F4A7C15u64;
+        bytes.extend((0..48).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            [0x00, 0x01, 0x0A, 0x10, 0xE8][(state % 5) as usize]
+        }));
+        let base = 0x1000;
+        let target = 0x100A;
+        let got = 0x100Ausize.to_le_bytes();
+        let table = [0x2000, target, 0x3000];
+        let segments = [(0..16, base), (16..bytes.len(), base + 0x100)];
+        let widths = [(target, 2), (0x0A, 1)];
+
+        check_traversal_invariants(&AbsoluteFinder::<LittleEndian>::new(target), &bytes);
+        check_traversal_invariants(
+            &AbsoluteFinder::<LittleEndian>::new(target).with_sign_extended_imm32(),
+            &bytes,
+        );
+        check_traversal_invariants(
+            &AbsoluteFinder::<LittleEndian>::new(target).with_pointer_width(PointerWidth::Ptr16),
+            &bytes,
+        );
+        check_traversal_invariants(
+            &AbsoluteFinder::<LittleEndian>::new(target).with_pointer_width(PointerWidth::Far16),
+            &bytes,
+        );
+        check_traversal_invariants(
+            &AbsoluteFinder::<LittleEndian>::new_range(0, 0x2000),
+            &bytes,
+        );
+        let aligned = AbsoluteFinder::<LittleEndian>::new(target).with_alignment(base + 3, 2);
+        check_traversal_invariants(&aligned, &bytes);
+        check_traversal_invariants(
+            &RelativeFinder::<LittleEndian>::new(base, 4, target),
+            &bytes,
+        );
+        let lengths = RelativeFinder::<LittleEndian>::with_lengths(base, &[4, 5, 6], target);
+        check_traversal_invariants(&lengths, &bytes);
+        let rel8 = RelativeFinder::<LittleEndian>::new(base, 1, target)
+            .with_displacement_width(DisplacementWidth::Rel8);
+        check_traversal_invariants(&rel8, &bytes);
+        check_traversal_invariants(
+            &RelativeAndAbsoluteFinder::<LittleEndian>::new(base, 4, target),
+            &bytes,
+        );
+        check_traversal_invariants(
+            &CodeHeuristicRelativeFinder::<LittleEndian>::new(base, target),
+            &bytes,
+        );
+        check_traversal_invariants(
+            &GotRelativeFinder::<LittleEndian>::new(base, 4, base + 0x10, &got, target),
+            &bytes,
+        );
+        check_traversal_invariants(
+            &SibFinder::<LittleEndian>::new(0x1000, 0, 1, target),
+            &bytes,
+        );
+        check_traversal_invariants(&MaskedFinder::<LittleEndian>::new(0xFF, 0x0A), &bytes);
+        check_traversal_invariants(&ModularFinder::<LittleEndian>::new(0x10, 0x0A), &bytes);
+        check_traversal_invariants(&IndirectFinder::<LittleEndian>::new(&table, target), &bytes);
+        check_traversal_invariants(&SelfPointerFinder::<LittleEndian>::new(base), &bytes);
+        check_traversal_invariants(
+            &SegmentedRelativeFinder::<LittleEndian>::new(&segments, 4, target),
+            &bytes,
+        );
+        check_traversal_invariants(&MixedWidthMultiFinder::<LittleEndian>::new(&widths), &bytes);
+        check_traversal_invariants(
+            &MultiTargetFinder::<LittleEndian>::from_range_stride(base, 4, base, base + 0x20, 2),
+            &bytes,
+        );
+        check_traversal_invariants(&Leb128RelativeFinder::new(base, target), &bytes);
+        check_traversal_invariants(&AsciiHexFinder::new(0x10), &bytes);
+        check_traversal_invariants(
+            &AbsoluteFinder::new_with_endian(target, Endianness::Big),
+            &bytes,
+        );
+        check_traversal_invariants(
+            &RelativeFinder::new_with_endian(base, 4, target, Endianness::Little),
+            &bytes,
+        );
+        check_traversal_invariants(
+            &RelativeAndAbsoluteFinder::<LittleEndian>::new(base, 4, target)
+                .with_modes(true, false),
+            &bytes,
+        );
+        check_traversal_invariants(
+            &RelativeAndAbsoluteFinder::<LittleEndian>::new(base, 4, target).with_modes(true, true),
+            &bytes,
+        );
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn check_all_addresses() {
+        // call 0x100A; 0x100A
+        let bytes = [
+            0xE8, 0x05, 0x00, 0x00, 0x00, 0x0A, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let searcher = RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x100A);
+        assert_eq!(searcher.all_addresses(&bytes).collect::<Vec<_>>(), [0x1001]);
+        let searcher = RelativeAndAbsoluteFinder::<LittleEndian>::new(0x1000, 4, 0x100A);
+        assert_eq!(
+            searcher.all_addresses(&bytes).collect::<Vec<_>>(),
+            [0x1001, 0x1005]
+        );
+        let searcher = AbsoluteFinder::<LittleEndian>::new(0x100A);
+        assert_eq!(
+            searcher.all_addresses(&bytes, 0x400000).collect::<Vec<_>>(),
+            [0x400005]
+        );
+        assert_eq!(searcher.all_addresses(&bytes, usize::MAX - 4).count(), 0);
+        assert_eq!(
+            searcher
+                .all_addresses(&bytes, usize::MAX - 5)
+                .collect::<Vec<_>>(),
+            [usize::MAX]
+        );
+    }
+
+    #[test]
+    fn check_does_match_past_end() {
+        let bytes = [0x0A, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let got = 0x100Ausize.to_le_bytes();
+        let table = [0x100A];
+        let segments = [(0..bytes.len(), 0x1000)];
+        let widths = [(0x100A, 2)];
+        let finders: [&dyn XRefFinder; 21] = [
+            &AbsoluteFinder::<LittleEndian>::new(0x100A),
+            &AbsoluteFinder::<LittleEndian>::new(0x100A).with_sign_extended_imm32(),
+            &AbsoluteFinder::<LittleEndian>::new(0x100A).with_pointer_width(PointerWidth::Far16),
+            &AbsoluteFinder::<LittleEndian>::new_range(0, usize::MAX),
+            &AbsoluteFinder::<LittleEndian>::new(0x100A).with_pointer_width(PointerWidth::Ptr32),
+            &RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x100A),
+            &RelativeFinder::<LittleEndian>::new(0x1000, 4, 0x100A)
+                .with_opcodes(&[RefMnemonic::Call]),
+            &RelativeAndAbsoluteFinder::<LittleEndian>::new(0x1000, 4, 0x100A),
+            &CodeHeuristicRelativeFinder::<LittleEndian>::new(0x1000, 0x100A),
+            &GotRelativeFinder::<LittleEndian>::new(0x1000, 4, 0x2000, &got, 0x100A),
+            &SibFinder::<LittleEndian>::new(0x1000, 0, 1, 0x100A),
+            &MaskedFinder::<LittleEndian>::new(0, 0),
+            &ModularFinder::<LittleEndian>::new(1, 0),
+            &IndirectFinder::<LittleEndian>::new(&table, 0x100A),
+            &SelfPointerFinder::<LittleEndian>::new(0x1000),
+            &SegmentedRelativeFinder::<LittleEndian>::new(&segments, 4, 0x100A),
+            &MixedWidthMultiFinder::<LittleEndian>::new(&widths),
+            &MultiTargetFinder::<LittleEndian>::from_range_stride(0x1000, 4, 0, usize::MAX, 1),
+            &Leb128RelativeFinder::new(0x1000, 0x100A),
+            &AsciiHexFinder::new(0x100A),
+            &AbsoluteFinder::new_with_endian(0x100A, Endianness::Big),
+        ];
+
+        for finder in finders {
+            for offset in [bytes.len(), bytes.len() + 2, usize::MAX - 1, usize::MAX] {
+                assert!(!finder.does_match(&bytes, offset), "{offset}");
+            }
+        }
     }
 }
@@ -0,0 +1,204 @@
+use alloc::vec::Vec;
+use byteorder::ByteOrder;
+use core::{iter, marker::PhantomData, mem::size_of};
+
+use crate::{
+    read::{fits, read_addr},
+    XRefFinder,
+};
+
+const REBASE_TYPE_POINTER: u8 = 1;
+
+const REBASE_OPCODE_MASK: u8 = 0xF0;
+const REBASE_IMMEDIATE_MASK: u8 = 0x0F;
+const REBASE_OPCODE_DONE: u8 = 0x00;
+const REBASE_OPCODE_SET_TYPE_IMM: u8 = 0x10;
+const REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB: u8 = 0x20;
+const REBASE_OPCODE_ADD_ADDR_ULEB: u8 = 0x30;
+const REBASE_OPCODE_ADD_ADDR_IMM_SCALED: u8 = 0x40;
+const REBASE_OPCODE_DO_REBASE_IMM_TIMES: u8 = 0x50;
+const REBASE_OPCODE_DO_REBASE_ULEB_TIMES: u8 = 0x60;
+const REBASE_OPCODE_DO_REBASE_ADD_ADDR_ULEB: u8 = 0x70;
+const REBASE_OPCODE_DO_REBASE_ULEB_TIMES_SKIPPING_ULEB: u8 = 0x80;
+
+/// The largest segment offset, which `rebases` yields
+pub(crate) const MAX_SEGMENT_OFFSET: u64 = u32::MAX as u64;
+
+/// A location, at which dyld slides a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rebase {
+    /// The `REBASE_TYPE_*` of the value, 1 for pointers
+    pub kind: u8,
+    pub segment_index: u8,
+    /// The offset of the value inside of its segment
+    pub segment_offset: u64,
+}
+
+/// Decodes an unsigned LEB128 value of up to 64 bits at `*position` and advances `*position` past it
+fn read_uleb128(opcodes: &[u8], position: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..u64::BITS).step_by(7) {
+        let byte = *opcodes.get(*position)?;
+        *position += 1;
+        value |= ((byte & 0x7F) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Decodes the rebase opcodes of the dyld info of a Mach-O binary into the locations, which are slid
+///
+/// Pointers are assumed to be `size_of::<usize>` bytes wide. Decoding stops at `REBASE_OPCODE_DONE`,
+/// at the end of `opcodes` or at the first malformed or unknown opcode.
+/// Repeated rebases, which would pass a segment offset of `u32::MAX`, are dropped, so malformed counts can't stall decoding.
+pub fn rebases(opcodes: &[u8]) -> impl Iterator<Item = Rebase> + '_ {
+    rebases_within(opcodes, MAX_SEGMENT_OFFSET)
+}
+
+/// Same as `rebases`, but drops the repeated rebases, which would pass `max_offset`
+fn rebases_within(opcodes: &[u8], max_offset: u64) -> impl Iterator<Item = Rebase> + '_ {
+    let pointer_size = size_of::<usize>() as u64;
+    // Limits the amount of rebases, so the run of `step` sized steps doesn't pass `max_offset`
+    let clamp = move |remaining: u64, address: u64, step: u64| match max_offset.checked_sub(address)
+    {
+        Some(distance) => remaining.min(distance / step.max(1) + 1),
+        None => 0,
+    };
+    let mut position = 0;
+    let mut kind = 0;
+    let mut segment_index = 0;
+    let mut address = 0u64;
+    // Rebases, which are still to be emitted by the current opcode, and the distance between them
+    let mut remaining = 0u64;
+    let mut step = 0u64;
+    iter::from_fn(move || loop {
+        if remaining > 0 {
+            remaining -= 1;
+            let segment_offset = address;
+            address = address.wrapping_add(step);
+            return Some(Rebase {
+                kind,
+                segment_index,
+                segment_offset,
+            });
+        }
+        let byte = *opcodes.get(position)?;
+        position += 1;
+        let immediate = byte & REBASE_IMMEDIATE_MASK;
+        match byte & REBASE_OPCODE_MASK {
+            REBASE_OPCODE_DONE => return None,
+            REBASE_OPCODE_SET_TYPE_IMM => kind = immediate,
+            REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB => {
+                segment_index = immediate;
+                address = read_uleb128(opcodes, &mut position)?;
+            }
+            REBASE_OPCODE_ADD_ADDR_ULEB => {
+                address = address.wrapping_add(read_uleb128(opcodes, &mut position)?);
+            }
+            REBASE_OPCODE_ADD_ADDR_IMM_SCALED => {
+                address = address.wrapping_add(immediate as u64 * pointer_size);
+            }
+            REBASE_OPCODE_DO_REBASE_IMM_TIMES => {
+                step = pointer_size;
+                remaining = clamp(immediate as u64, address, step);
+            }
+            REBASE_OPCODE_DO_REBASE_ULEB_TIMES => {
+                step = pointer_size;
+                remaining = clamp(read_uleb128(opcodes, &mut position)?, address, step);
+            }
+            REBASE_OPCODE_DO_REBASE_ADD_ADDR_ULEB => {
+                step = read_uleb128(opcodes, &mut position)?.wrapping_add(pointer_size);
+                remaining = clamp(1, address, step);
+            }
+            REBASE_OPCODE_DO_REBASE_ULEB_TIMES_SKIPPING_ULEB => {
+                let count = read_uleb128(opcodes, &mut position)?;
+                step = read_uleb128(opcodes, &mut position)?.wrapping_add(pointer_size);
+                remaining = clamp(count, address, step);
+            }
+            _ => return None,
+        }
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MachORebaseFinder<'a, Endian: ByteOrder> {
+    opcodes: &'a [u8],
+    segment_index: u8,
+    slide: usize,
+    target: usize,
+    endian: PhantomData<Endian>,
+}
+
+impl<'a, Endian: ByteOrder> MachORebaseFinder<'a, Endian> {
+    /// Creates a new MachORebaseFinder, that can then find pointers, which point to `target` once dyld has slid them
+    ///
+    /// The scanned bytes have to be the contents of the segment with the index `segment_index`.
+    /// Only offsets, at which the rebase opcodes place a pointer, are considered.
+    ///
+    /// Arguments:
+    ///
+    /// * `opcodes`: The rebase opcodes of the dyld info (`LC_DYLD_INFO`)
+    /// * `segment_index`: The index of the scanned segment
+    /// * `slide`: The amount of bytes, which the binary has been moved by
+    /// * `target`: The address, which the slid pointer should point to
+    pub fn new(opcodes: &'a [u8], segment_index: u8, slide: usize, target: usize) -> Self {
+        Self {
+            opcodes,
+            segment_index,
+            slide,
+            target,
+            endian: PhantomData,
+        }
+    }
+
+    fn is_slid_target(&self, bytes: &[u8], offset: usize) -> bool {
+        fits(bytes, offset, size_of::<usize>())
+            && read_addr::<Endian>(bytes, offset, size_of::<usize>()).wrapping_add(self.slide)
+                == self.target
+    }
+
+    /// The offsets of the pointers in the scanned segment of `len` bytes
+    fn pointers(&self, len: usize) -> impl Iterator<Item = usize> + 'a {
+        let segment_index = self.segment_index;
+        rebases_within(self.opcodes, len as u64)
+            .filter(move |rebase| {
+                rebase.kind == REBASE_TYPE_POINTER && rebase.segment_index == segment_index
+            })
+            .filter_map(|rebase| usize::try_from(rebase.segment_offset).ok())
+    }
+
+    /// Finds all references by walking the rebase opcodes once
+    ///
+    /// Unlike `all`, the references are yielded in the order of the opcodes
+    pub fn all_rebased<'b>(&'b self, bytes: &'b [u8]) -> impl Iterator<Item = usize> + 'b {
+        self.pointers(bytes.len())
+            .filter(move |&offset| self.is_slid_target(bytes, offset))
+    }
+}
+
+impl<Endian: ByteOrder> XRefFinder for MachORebaseFinder<'_, Endian> {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        self.is_slid_target(bytes, offset)
+            && self.pointers(bytes.len()).any(|pointer| pointer == offset)
+    }
+
+    fn match_width(&self) -> usize {
+        size_of::<usize>()
+    }
+
+    fn next_from(&self, bytes: &[u8], start: usize) -> Option<usize> {
+        self.all_rebased(bytes)
+            .filter(|&offset| offset >= start)
+            .min()
+    }
+
+    fn all(&self, bytes: &[u8]) -> impl Iterator<Item = usize> {
+        // Walks the opcodes once instead of decoding them for every offset
+        let mut offsets: Vec<usize> = self.all_rebased(bytes).collect();
+        offsets.sort_unstable();
+        offsets.dedup();
+        offsets.into_iter()
+    }
+}
@@ -0,0 +1,43 @@
+use byteorder::ByteOrder;
+use core::{marker::PhantomData, mem::size_of};
+
+use crate::{
+    read::{fits, read_addr},
+    XRefFinder,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct MaskedFinder<Endian: ByteOrder> {
+    mask: usize,
+    target: usize,
+    endian: PhantomData<Endian>,
+}
+
+impl<Endian: ByteOrder> MaskedFinder<Endian> {
+    /// Creates a new MaskedFinder, that can then find absolute values, whose masked bits equal `target`
+    ///
+    /// Arguments:
+    ///
+    /// * `mask`: The bits of the absolute value, which are compared
+    /// * `target`: The value, which the masked bits should equal
+    pub fn new(mask: usize, target: usize) -> Self {
+        Self {
+            mask,
+            target,
+            endian: PhantomData,
+        }
+    }
+}
+
+impl<Endian: ByteOrder> XRefFinder for MaskedFinder<Endian> {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        if !fits(bytes, offset, size_of::<usize>()) {
+            return false;
+        }
+        read_addr::<Endian>(bytes, offset, size_of::<usize>()) & self.mask == self.target
+    }
+
+    fn match_width(&self) -> usize {
+        size_of::<usize>()
+    }
+}
@@ -0,0 +1,97 @@
+use core::iter::{self, FusedIterator};
+
+use crate::XRefFinder;
+
+/// Yields the references of `finder` in `bytes` by resuming `next_from` after every reference
+///
+/// Unlike `Matches`, this takes ownership of the finder, so it can be returned together with a finder built on the fly.
+pub(crate) fn resume_iter<'a, F: XRefFinder>(
+    finder: F,
+    bytes: &'a [u8],
+) -> impl Iterator<Item = usize> + use<'a, F> {
+    let mut start = 0;
+    iter::from_fn(move || {
+        let offset = finder.next_from(bytes, start)?;
+        start = offset + 1;
+        Some(offset)
+    })
+}
+
+/// An iterator over the references in a buffer, refer to `XRefFinder::matches`
+///
+/// The offsets are always relative to the start of the whole buffer, so finders depending on the location of a reference
+/// (e.g. `RelativeFinder`) stay correct, unlike calling `next` on subslices.
+#[derive(Debug)]
+pub struct Matches<'a, F: ?Sized> {
+    finder: &'a F,
+    bytes: &'a [u8],
+    /// The first offset, which hasn't been yielded from the front
+    front: usize,
+    /// The offset after the last one, which hasn't been yielded from the back
+    back: usize,
+}
+
+impl<'a, F: XRefFinder + ?Sized> Matches<'a, F> {
+    /// Creates a new Matches, that yields the references of `finder` in `bytes`
+    ///
+    /// This also works for trait objects, which can't call `XRefFinder::matches`
+    pub fn new(finder: &'a F, bytes: &'a [u8]) -> Self {
+        Self {
+            finder,
+            bytes,
+            front: 0,
+            back: bytes.len(),
+        }
+    }
+
+    /// The offset, from which the search from the front continues
+    pub fn position(&self) -> usize {
+        self.front
+    }
+}
+
+impl<F: ?Sized> Clone for Matches<'_, F> {
+    fn clone(&self) -> Self {
+        Self { ..*self }
+    }
+}
+
+impl<F: XRefFinder + ?Sized> Iterator for Matches<'_, F> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.front >= self.back {
+            return None;
+        }
+        match self.finder.next_from(self.bytes, self.front) {
+            Some(offset) if offset < self.back => {
+                self.front = offset + 1;
+                Some(offset)
+            }
+            _ => {
+                self.front = self.back;
+                None
+            }
+        }
+    }
+}
+
+impl<F: XRefFinder + ?Sized> DoubleEndedIterator for Matches<'_, F> {
+    fn next_back(&mut self) -> Option<usize> {
+        if self.front >= self.back {
+            return None;
+        }
+        match self.finder.prev_from(self.bytes, self.back) {
+            Some(offset) if offset >= self.front => {
+                self.back = offset;
+                Some(offset)
+            }
+            _ => {
+                self.back = self.front;
+                None
+            }
+        }
+    }
+}
+
+impl<F: XRefFinder + ?Sized> FusedIterator for Matches<'_, F> {}
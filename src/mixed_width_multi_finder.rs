@@ -0,0 +1,66 @@
+use byteorder::ByteOrder;
+use core::{marker::PhantomData, mem::size_of};
+
+use crate::{
+    read::{fits, read_addr},
+    XRefFinder,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct MixedWidthMultiFinder<'a, Endian: ByteOrder> {
+    targets: &'a [(usize, usize)],
+    endian: PhantomData<Endian>,
+}
+
+impl<'a, Endian: ByteOrder> MixedWidthMultiFinder<'a, Endian> {
+    /// Creates a new MixedWidthMultiFinder, that can then find absolute cross references to any of the `targets`
+    ///
+    /// Arguments:
+    ///
+    /// * `targets`: Pairs of `(target, width)`, where `width` is the amount of bytes the absolute reference to `target` occupies
+    ///
+    /// # Panics
+    ///
+    /// Panics if a width is 0 or larger than `size_of::<usize>()`
+    pub fn new(targets: &'a [(usize, usize)]) -> Self {
+        assert!(
+            targets
+                .iter()
+                .all(|&(_, width)| (1..=size_of::<usize>()).contains(&width)),
+            "widths have to be between 1 and size_of::<usize>()"
+        );
+        Self {
+            targets,
+            endian: PhantomData,
+        }
+    }
+
+    /// Returns the first `(target, width)` pair, which is referenced at `offset`
+    pub fn match_at(&self, bytes: &[u8], offset: usize) -> Option<(usize, usize)> {
+        self.targets.iter().copied().find(|&(target, width)| {
+            fits(bytes, offset, width) && read_addr::<Endian>(bytes, offset, width) == target
+        })
+    }
+}
+
+impl<Endian: ByteOrder> XRefFinder for MixedWidthMultiFinder<'_, Endian> {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        self.match_at(bytes, offset).is_some()
+    }
+
+    fn match_width(&self) -> usize {
+        self.targets
+            .iter()
+            .map(|&(_, width)| width)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn min_bytes(&self) -> usize {
+        self.targets
+            .iter()
+            .map(|&(_, width)| width)
+            .min()
+            .unwrap_or(0)
+    }
+}
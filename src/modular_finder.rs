@@ -0,0 +1,50 @@
+use byteorder::ByteOrder;
+use core::{marker::PhantomData, mem::size_of};
+
+use crate::{
+    read::{fits, read_addr},
+    XRefFinder,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct ModularFinder<Endian: ByteOrder> {
+    modulus: usize,
+    residue: usize,
+    endian: PhantomData<Endian>,
+}
+
+impl<Endian: ByteOrder> ModularFinder<Endian> {
+    /// Creates a new ModularFinder, that can then find absolute values, which are congruent to `residue` modulo `modulus`
+    ///
+    /// Unlike `MaskedFinder`, `modulus` doesn't have to be a power of two.
+    ///
+    /// Arguments:
+    ///
+    /// * `modulus`: The value, by which the absolute value is divided
+    /// * `residue`: The remainder, which the division should leave
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus` is 0
+    pub fn new(modulus: usize, residue: usize) -> Self {
+        assert!(modulus != 0, "modulus must not be 0");
+        Self {
+            modulus,
+            residue,
+            endian: PhantomData,
+        }
+    }
+}
+
+impl<Endian: ByteOrder> XRefFinder for ModularFinder<Endian> {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        if !fits(bytes, offset, size_of::<usize>()) {
+            return false;
+        }
+        read_addr::<Endian>(bytes, offset, size_of::<usize>()) % self.modulus == self.residue
+    }
+
+    fn match_width(&self) -> usize {
+        size_of::<usize>()
+    }
+}
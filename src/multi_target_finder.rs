@@ -0,0 +1,141 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use byteorder::ByteOrder;
+use core::{marker::PhantomData, mem::size_of};
+
+use crate::{
+    read::{fits, read_addr, read_disp},
+    relative_finder::resolve_relative,
+    XRefFinder,
+};
+
+#[derive(Debug, Clone)]
+enum Targets {
+    /// Every `stride`th address in `start..end`
+    Strided {
+        start: usize,
+        end: usize,
+        stride: usize,
+    },
+    /// Sorted and deduplicated addresses
+    #[cfg(feature = "alloc")]
+    Sorted(Vec<usize>),
+}
+
+impl Targets {
+    fn contains(&self, address: usize) -> bool {
+        match *self {
+            Self::Strided { start, end, stride } => {
+                (start..end).contains(&address) && (address - start).is_multiple_of(stride)
+            }
+            #[cfg(feature = "alloc")]
+            Self::Sorted(ref targets) => targets.binary_search(&address).is_ok(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MultiTargetFinder<Endian: ByteOrder> {
+    base_address: usize,
+    instruction_length: usize,
+    targets: Targets,
+    endian: PhantomData<Endian>,
+}
+
+impl<Endian: ByteOrder> MultiTargetFinder<Endian> {
+    /// Creates a new MultiTargetFinder, that can then find relative and absolute cross references to any of the `targets`
+    ///
+    /// The targets are sorted once, so each offset is checked in `O(log targets)` instead of scanning once per target.
+    ///
+    /// For the other arguments refer to `RelativeFinder`
+    #[cfg(feature = "alloc")]
+    pub fn new(
+        base_address: usize,
+        instruction_length: usize,
+        targets: impl IntoIterator<Item = usize>,
+    ) -> Self {
+        let mut targets: Vec<usize> = targets.into_iter().collect();
+        targets.sort_unstable();
+        targets.dedup();
+        Self {
+            base_address,
+            instruction_length,
+            targets: Targets::Sorted(targets),
+            endian: PhantomData,
+        }
+    }
+
+    /// Creates a new MultiTargetFinder, that can then find relative and absolute cross references to
+    /// `start`, `start + stride`, `start + 2 * stride`, ... up to (excluding) `end`
+    ///
+    /// The targets are never materialized, so this is suited for large regularly spaced tables.
+    ///
+    /// For the other arguments refer to `RelativeFinder`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stride` is 0
+    pub fn from_range_stride(
+        base_address: usize,
+        instruction_length: usize,
+        start: usize,
+        end: usize,
+        stride: usize,
+    ) -> Self {
+        assert!(stride != 0, "stride must not be 0");
+        Self {
+            base_address,
+            instruction_length,
+            targets: Targets::Strided { start, end, stride },
+            endian: PhantomData,
+        }
+    }
+
+    /// Returns the target, which is referenced at `offset`
+    ///
+    /// The relative interpretation is checked first
+    pub fn match_at(&self, bytes: &[u8], offset: usize) -> Option<usize> {
+        if fits(bytes, offset, size_of::<i32>()) {
+            let value = read_disp::<Endian>(bytes, offset, size_of::<i32>());
+            let address = resolve_relative(
+                self.base_address.wrapping_add(offset),
+                self.instruction_length,
+                value,
+            );
+            if self.targets.contains(address) {
+                return Some(address);
+            }
+        }
+        if fits(bytes, offset, size_of::<usize>()) {
+            let address = read_addr::<Endian>(bytes, offset, size_of::<usize>());
+            if self.targets.contains(address) {
+                return Some(address);
+            }
+        }
+        None
+    }
+
+    /// Finds all references and pairs them with the target, which they reference
+    ///
+    /// Refer to `match_at`
+    pub fn all_with_target<'a>(
+        &'a self,
+        bytes: &'a [u8],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        (0..bytes.len()).filter_map(move |i| self.match_at(bytes, i).map(|target| (i, target)))
+    }
+}
+
+impl<Endian: ByteOrder> XRefFinder for MultiTargetFinder<Endian> {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        self.match_at(bytes, offset).is_some()
+    }
+
+    fn match_width(&self) -> usize {
+        size_of::<usize>()
+    }
+
+    fn min_bytes(&self) -> usize {
+        size_of::<i32>()
+    }
+}
@@ -0,0 +1,24 @@
+use byteorder::ByteOrder;
+
+/// Checks if `width` bytes starting at `offset` are within `bytes`
+///
+/// Unlike `bytes.len() - offset >= width`, this doesn't overflow for offsets past the end of `bytes`
+pub(crate) fn fits(bytes: &[u8], offset: usize, width: usize) -> bool {
+    offset
+        .checked_add(width)
+        .is_some_and(|end| end <= bytes.len())
+}
+
+/// Reads a zero-extended unsigned value of `width` bytes at `offset`
+///
+/// The caller has to make sure that `offset + width` is within `bytes` and `width` is at most 8
+pub(crate) fn read_addr<Endian: ByteOrder>(bytes: &[u8], offset: usize, width: usize) -> usize {
+    Endian::read_uint(&bytes[offset..offset + width], width) as usize
+}
+
+/// Reads a sign-extended signed value of `width` bytes at `offset`
+///
+/// The caller has to make sure that `offset + width` is within `bytes` and `width` is at most 8
+pub(crate) fn read_disp<Endian: ByteOrder>(bytes: &[u8], offset: usize, width: usize) -> isize {
+    Endian::read_int(&bytes[offset..offset + width], width) as isize
+}
@@ -2,12 +2,49 @@ use core::{marker::PhantomData, mem::size_of};
 
 use byteorder::ByteOrder;
 
-use crate::{absolute_finder::does_match_absolute, XRefFinder};
+use crate::{
+    absolute_finder::does_match_absolute,
+    read::{fits, read_addr, read_disp},
+    relative_finder::{does_match_relative, resolve_relative},
+    XRefFinder,
+};
 
+/// The interpretation under which a reference was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReferenceKind {
+    Relative,
+    Absolute,
+}
+
+/// The interpretation, which is reported first if both interpretations match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Prefer {
+    #[default]
+    Relative,
+    Absolute,
+}
+
+/// Both interpretations of an offset, as decoded by `RelativeAndAbsoluteFinder::explain`
+///
+/// The values are `None` if not enough bytes are left at the offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Explanation {
+    /// The value, if it is read as an absolute address
+    pub absolute_value: Option<usize>,
+    /// The value, if it is read as a relative offset
+    pub displacement: Option<isize>,
+    /// The address, which the relative offset leads to
+    pub relative_address: Option<usize>,
+    pub absolute_matches: bool,
+    pub relative_matches: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
 pub struct RelativeAndAbsoluteFinder<Endian: ByteOrder> {
     base_address: usize,
     instruction_length: usize,
     target: usize,
+    prefer: Prefer,
     endian: PhantomData<Endian>,
 }
 
@@ -20,33 +57,156 @@ impl<Endian: ByteOrder> RelativeAndAbsoluteFinder<Endian> {
             base_address,
             instruction_length,
             target,
+            prefer: Prefer::default(),
             endian: PhantomData,
         }
     }
+
+    /// Sets the interpretation, which `match_kind` reports if both interpretations match. Defaults to `Prefer::Relative`.
+    pub fn with_preference(mut self, prefer: Prefer) -> Self {
+        self.prefer = prefer;
+        self
+    }
+
+    fn matches_kind(&self, bytes: &[u8], offset: usize, kind: ReferenceKind) -> bool {
+        match kind {
+            ReferenceKind::Relative => {
+                fits(bytes, offset, size_of::<i32>())
+                    && does_match_relative::<Endian>(
+                        bytes,
+                        offset,
+                        self.base_address,
+                        self.instruction_length,
+                        0,
+                        self.target,
+                    )
+            }
+            ReferenceKind::Absolute => {
+                fits(bytes, offset, size_of::<usize>())
+                    && does_match_absolute::<Endian>(bytes, offset, self.target)
+            }
+        }
+    }
+
+    /// Both interpretations, the preferred one first
+    fn kinds(&self) -> [ReferenceKind; 2] {
+        match self.prefer {
+            Prefer::Relative => [ReferenceKind::Relative, ReferenceKind::Absolute],
+            Prefer::Absolute => [ReferenceKind::Absolute, ReferenceKind::Relative],
+        }
+    }
+
+    /// Checks how the `offset` in `bytes` references the target
+    ///
+    /// If both interpretations match, the preferred one is returned
+    pub fn match_kind(&self, bytes: &[u8], offset: usize) -> Option<ReferenceKind> {
+        self.kinds()
+            .into_iter()
+            .find(|&kind| self.matches_kind(bytes, offset, kind))
+    }
+
+    /// Finds all references and pairs them with the interpretation, under which they reference the target
+    ///
+    /// If both interpretations match at an offset, it is yielded twice, the preferred interpretation first
+    pub fn all_with_kind<'a>(
+        &'a self,
+        bytes: &'a [u8],
+    ) -> impl Iterator<Item = (usize, ReferenceKind)> + 'a {
+        (0..bytes.len()).flat_map(move |i| {
+            self.kinds()
+                .into_iter()
+                .filter(move |&kind| self.matches_kind(bytes, i, kind))
+                .map(move |kind| (i, kind))
+        })
+    }
+
+    /// Finds all references and yields their addresses (`base_address + offset`) instead of their offsets
+    ///
+    /// References, whose address would leave the address space, are skipped
+    pub fn all_addresses<'a>(&'a self, bytes: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        self.all(bytes)
+            .filter_map(|offset| self.base_address.checked_add(offset))
+    }
+
+    /// Decodes both interpretations of `offset`, regardless of whether they match
+    ///
+    /// This is meant for debugging, e.g. to find the correct `instruction_length`
+    pub fn explain(&self, bytes: &[u8], offset: usize) -> Explanation {
+        let remaining = bytes.len().saturating_sub(offset);
+        let absolute_value = (remaining >= size_of::<usize>())
+            .then(|| read_addr::<Endian>(bytes, offset, size_of::<usize>()));
+        let displacement = (remaining >= size_of::<i32>())
+            .then(|| read_disp::<Endian>(bytes, offset, size_of::<i32>()));
+        let relative_address = displacement.map(|displacement| {
+            let address = self.base_address.wrapping_add(offset);
+            resolve_relative(address, self.instruction_length, displacement)
+        });
+        Explanation {
+            absolute_value,
+            displacement,
+            relative_address,
+            absolute_matches: absolute_value == Some(self.target),
+            relative_matches: relative_address == Some(self.target),
+        }
+    }
+
+    /// Returns a view of this finder, which only checks the enabled interpretations
+    ///
+    /// Disabling both results in a finder, that never matches
+    pub fn with_modes(&self, relative: bool, absolute: bool) -> impl XRefFinder + '_ {
+        Modes {
+            finder: self,
+            relative,
+            absolute,
+        }
+    }
 }
 
-impl<Endian: ByteOrder> XRefFinder for RelativeAndAbsoluteFinder<Endian> {
+struct Modes<'a, Endian: ByteOrder> {
+    finder: &'a RelativeAndAbsoluteFinder<Endian>,
+    relative: bool,
+    absolute: bool,
+}
+
+impl<Endian: ByteOrder> XRefFinder for Modes<'_, Endian> {
     fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
-        use crate::relative_finder::does_match_relative;
-
-        if bytes.len() - offset >= size_of::<i32>()
-            && does_match_relative::<Endian>(
-                bytes,
-                offset,
-                self.base_address,
-                self.instruction_length,
-                self.target,
-            )
-        {
-            return true;
+        (self.relative
+            && self
+                .finder
+                .matches_kind(bytes, offset, ReferenceKind::Relative))
+            || (self.absolute
+                && self
+                    .finder
+                    .matches_kind(bytes, offset, ReferenceKind::Absolute))
+    }
+
+    fn match_width(&self) -> usize {
+        if self.absolute {
+            size_of::<usize>()
+        } else {
+            size_of::<i32>()
         }
+    }
 
-        if bytes.len() - offset >= size_of::<usize>()
-            && does_match_absolute::<Endian>(bytes, offset, self.target)
-        {
-            return true;
+    fn min_bytes(&self) -> usize {
+        if self.relative {
+            size_of::<i32>()
+        } else {
+            self.match_width()
         }
+    }
+}
+
+impl<Endian: ByteOrder> XRefFinder for RelativeAndAbsoluteFinder<Endian> {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        self.match_kind(bytes, offset).is_some()
+    }
+
+    fn match_width(&self) -> usize {
+        size_of::<usize>()
+    }
 
-        false
+    fn min_bytes(&self) -> usize {
+        size_of::<i32>()
     }
 }
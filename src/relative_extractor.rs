@@ -0,0 +1,75 @@
+use byteorder::ByteOrder;
+use core::{marker::PhantomData, mem::size_of};
+
+use crate::{
+    code_heuristic_relative_finder::{branch_mnemonic, RefMnemonic},
+    read::{fits, read_disp},
+};
+
+/// Resolves every relative offset in a buffer, instead of matching them against a target
+///
+/// This is meant for building a database of references, e.g. a call graph in combination with `with_opcodes`.
+#[derive(Debug, Clone, Copy)]
+pub struct RelativeExtractor<Endian: ByteOrder> {
+    base_address: usize,
+    instruction_length: usize,
+    /// Bit `n` is set, if the `n`th `RefMnemonic` may precede a reference. `None` accepts every offset.
+    mnemonics: Option<u8>,
+    endian: PhantomData<Endian>,
+}
+
+impl<Endian: ByteOrder> RelativeExtractor<Endian> {
+    /// Creates a new RelativeExtractor, that can then resolve the relative offsets in a buffer
+    ///
+    /// For arguments refer to `RelativeFinder::new`
+    pub fn new(base_address: usize, instruction_length: usize) -> Self {
+        Self {
+            base_address,
+            instruction_length,
+            mnemonics: None,
+            endian: PhantomData,
+        }
+    }
+
+    /// Only resolves relative offsets, which are preceded by the opcode of a branch of one of the `kinds`
+    ///
+    /// Refer to `RelativeFinder::with_opcodes`
+    pub fn with_opcodes(mut self, kinds: &[RefMnemonic]) -> Self {
+        self.mnemonics = Some(
+            kinds
+                .iter()
+                .fold(0, |mnemonics, &kind| mnemonics | 1 << kind as u8),
+        );
+        self
+    }
+
+    /// Returns the address, which the relative offset at `offset` leads to
+    ///
+    /// The address is the one, which a `RelativeFinder` with the same arguments would match.
+    /// Returns `None` if the offset is filtered out, or the address wraps around the address space,
+    /// which is almost never intended by a compiler.
+    pub fn resolve_at(&self, bytes: &[u8], offset: usize) -> Option<usize> {
+        if !fits(bytes, offset, size_of::<i32>()) {
+            return None;
+        }
+        if let Some(mnemonics) = self.mnemonics {
+            let mnemonic = branch_mnemonic(bytes, offset)?;
+            if mnemonics & 1 << mnemonic as u8 == 0 {
+                return None;
+            }
+        }
+        let displacement = read_disp::<Endian>(bytes, offset, size_of::<i32>());
+        let next_instruction = self
+            .base_address
+            .checked_add(offset)?
+            .checked_add(self.instruction_length)?;
+        next_instruction.checked_add_signed(displacement)
+    }
+
+    /// Resolves every relative offset and yields `(offset, target)`
+    ///
+    /// Refer to `resolve_at`
+    pub fn extract<'a>(&'a self, bytes: &'a [u8]) -> impl Iterator<Item = (usize, usize)> + 'a {
+        (0..bytes.len()).filter_map(move |i| self.resolve_at(bytes, i).map(|target| (i, target)))
+    }
+}
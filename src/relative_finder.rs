@@ -1,7 +1,14 @@
-use byteorder::ByteOrder;
-use core::{marker::PhantomData, mem::size_of};
+use core::{fmt, marker::PhantomData, mem::size_of};
 
-use crate::XRefFinder;
+use crate::{
+    code_heuristic_relative_finder::{branch_mnemonic, RefMnemonic, BRANCH_OPCODE_LENGTH},
+    read::fits,
+    target_range::TargetRange,
+    Decoder, XRefFinder,
+};
+
+/// The length of the longest x86 instruction
+pub const MAX_INSTRUCTION_LENGTH: usize = 15;
 
 /// Verifies that an relative offset interpretation of `base_address`, `instruction_length` and `offset` would lead the processor to `target`
 pub const fn is_relative_match(
@@ -10,74 +17,447 @@ pub const fn is_relative_match(
     offset: isize,
     target: usize,
 ) -> bool {
-    let mut address = address + instruction_length;
-    if offset.is_negative() {
-        address -= offset.unsigned_abs();
-    } else {
-        address += offset.unsigned_abs();
+    resolve_relative(address, instruction_length, offset) == target
+}
+
+/// Computes the address, which a relative offset at `address` would lead the processor to
+///
+/// Like the processor, this wraps around at the ends of the address space
+pub(crate) const fn resolve_relative(
+    address: usize,
+    instruction_length: usize,
+    offset: isize,
+) -> usize {
+    address
+        .wrapping_add(instruction_length)
+        .wrapping_add_signed(offset)
+}
+
+/// The displacement required to reach a target doesn't fit into a relative offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfRange;
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("target is out of range of a relative offset")
     }
-    address == target
 }
 
-pub(crate) fn does_match_relative<Endian: ByteOrder>(
+impl core::error::Error for OutOfRange {}
+
+/// Computes the relative offset, which has to be written at `offset` to make the instruction reference `target`
+///
+/// Like the processor, this wraps around at the ends of the address space.
+/// For arguments refer to `RelativeFinder::new`
+pub fn displacement_for(
+    base_address: usize,
+    instruction_length: usize,
+    offset: usize,
+    target: usize,
+) -> Result<i32, OutOfRange> {
+    let next_instruction = base_address
+        .wrapping_add(offset)
+        .wrapping_add(instruction_length);
+    i32::try_from(target.wrapping_sub(next_instruction) as isize).map_err(|_| OutOfRange)
+}
+
+pub(crate) fn does_match_relative<D: Decoder>(
     bytes: &[u8],
     offset: usize,
     base_address: usize,
     instruction_length: usize,
+    rip_adjust: isize,
     target: usize,
 ) -> bool {
-    let value = Endian::read_i32(&bytes[offset..offset + size_of::<i32>()]);
+    let value = D::read_displacement(bytes, offset, size_of::<i32>());
     is_relative_match(
-        base_address + offset,
+        base_address.wrapping_add(offset),
         instruction_length,
-        value as isize,
+        value.wrapping_add(rip_adjust),
         target,
     )
 }
 
-pub struct RelativeFinder<Endian: ByteOrder> {
+/// The width of the relative offset, which a `RelativeFinder` reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DisplacementWidth {
+    /// `i8`, e.g. `jmp rel8` (`EB`)
+    Rel8,
+    /// `i16`, e.g. 16-bit branches
+    Rel16,
+    /// `i32`
+    #[default]
+    Rel32,
+}
+
+impl DisplacementWidth {
+    /// The amount of bytes the relative offset occupies
+    pub const fn bytes(self) -> usize {
+        match self {
+            Self::Rel8 => size_of::<i8>(),
+            Self::Rel16 => size_of::<i16>(),
+            Self::Rel32 => size_of::<i32>(),
+        }
+    }
+}
+
+/// The instruction lengths, which a `RelativeFinder` tries
+#[derive(Debug, Clone, Copy)]
+enum InstructionLengths {
+    Single(usize),
+    /// Bit `n` is set, if `n` is a candidate
+    Set(u16),
+}
+
+impl InstructionLengths {
+    /// Yields the candidates in ascending order
+    fn iter(self) -> impl Iterator<Item = usize> {
+        let (single, set) = match self {
+            Self::Single(length) => (Some(length), 0),
+            Self::Set(set) => (None, set),
+        };
+        single
+            .into_iter()
+            .chain((0..=MAX_INSTRUCTION_LENGTH).filter(move |&n| set & (1 << n) != 0))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RelativeFinder<D: Decoder> {
     base_address: usize,
-    instruction_length: usize,
-    target: usize,
-    endian: PhantomData<Endian>,
+    instruction_lengths: InstructionLengths,
+    displacement_width: usize,
+    rip_adjust: isize,
+    /// The bits, which the processor keeps of a resolved address, set by `with_prefixes`
+    address_mask: usize,
+    /// Bit `n` is set, if the `n`th `RefMnemonic` may precede a reference. `None` accepts every reference.
+    mnemonics: Option<u8>,
+    targets: TargetRange,
+    decoder: PhantomData<D>,
 }
 
-impl<Endian: ByteOrder> RelativeFinder<Endian> {
+impl<D: Decoder> RelativeFinder<D> {
     /// Creates a new RelativeFinder, that can then find relative cross references
     ///
     /// Arguments:
     ///
     /// * `base_address`: Base address of relative references, this is useful when the memory you are scanning has been moved.
     /// * `instruction_length`: The amount of bytes to skip from the relative offset.
-    ///                         Most instructions, that use relative offsets, end in the relative offset,
-    ///                         so this is the size of the relative offset type (`i32`; `size_of::<i32>` = 4)
-    ///                         If a instruction has the relative offset in the middle (e.g. cmp) then you need to set this to
-    ///                         `size_of::<i32>` + how many bytes come after the relative offset.
-    ///                         Example: 48 83 3D [EF BE 00 00] 00    cmp $0x0, 0xBEEF(%rip) ; square brackets indicate relative offset
-    ///                         here there is an additonal byte after the relative offset -> `instruction_length` = `size_of::<i32>` + 1 = 5
+    ///   Most instructions, that use relative offsets, end in the relative offset,
+    ///   so this is the size of the relative offset type (`i32`; `size_of::<i32>` = 4)
+    ///   If a instruction has the relative offset in the middle (e.g. cmp) then you need to set this to
+    ///   `size_of::<i32>` + how many bytes come after the relative offset.
+    ///   Example: 48 83 3D [EF BE 00 00] 00    cmp $0x0, 0xBEEF(%rip) ; square brackets indicate relative offset
+    ///   here there is an additonal byte after the relative offset -> `instruction_length` = `size_of::<i32>` + 1 = 5
     /// * `target`: The address, which the reference should point to
     pub fn new(base_address: usize, instruction_length: usize, target: usize) -> Self {
         Self {
             base_address,
-            instruction_length,
-            target,
-            endian: PhantomData,
+            instruction_lengths: InstructionLengths::Single(instruction_length),
+            displacement_width: size_of::<i32>(),
+            rip_adjust: 0,
+            address_mask: usize::MAX,
+            mnemonics: None,
+            targets: TargetRange::exact(target),
+            decoder: PhantomData,
+        }
+    }
+
+    /// Creates a new RelativeFinder, that can then find relative cross references to any address in `lo..hi`
+    ///
+    /// This finds e.g. jumps into the middle of a function. An empty range (`lo >= hi`) never matches.
+    ///
+    /// For the other arguments refer to `new`
+    pub fn new_range(base_address: usize, instruction_length: usize, lo: usize, hi: usize) -> Self {
+        Self {
+            targets: TargetRange::new(lo, hi),
+            ..Self::new(base_address, instruction_length, 0)
+        }
+    }
+
+    /// Creates a new RelativeFinder, which uses the end of a section as its base address
+    ///
+    /// This is the same as `new` with `section_end` as `base_address`, so a reference at `offset` resolves to
+    /// `section_end + offset + instruction_length + displacement`. Negative displacements go backwards from the end
+    /// and therefore resolve into the section.
+    pub fn from_section_end(section_end: usize, instruction_length: usize, target: usize) -> Self {
+        Self::new(section_end, instruction_length, target)
+    }
+
+    /// Creates a new RelativeFinder, that tries every one of the `instruction_lengths` at each offset
+    ///
+    /// This is useful, if the exact instruction is unknown, e.g. because of prefixes or trailing immediates.
+    /// Use `match_at` to find out which length matched.
+    ///
+    /// For the other arguments refer to `new`
+    ///
+    /// # Panics
+    ///
+    /// Panics if an instruction length is larger than `MAX_INSTRUCTION_LENGTH`
+    pub fn with_lengths(base_address: usize, instruction_lengths: &[usize], target: usize) -> Self {
+        let set = instruction_lengths.iter().fold(0u16, |set, &length| {
+            assert!(
+                length <= MAX_INSTRUCTION_LENGTH,
+                "instruction lengths have to be at most 15"
+            );
+            set | 1 << length
+        });
+        Self {
+            instruction_lengths: InstructionLengths::Set(set),
+            ..Self::new(base_address, 0, target)
+        }
+    }
+
+    /// Creates a new RelativeFinder for relative offsets of instructions with the given size override prefixes
+    ///
+    /// The operand-size override (`66`) shrinks the relative offset of near branches to 16 bits,
+    /// the instruction length is then `size_of::<i16>`, and truncates the resulting address to 16 bits (`ip`).
+    /// Intel 64 processors ignore it for near branches in 64 bit mode, so it shouldn't be set for such code.
+    /// The address-size override (`67`) doesn't change the width of a relative offset,
+    /// it turns `rip` into `eip`, so the resulting address is truncated to 32 bits.
+    /// If both are set, the address is truncated to 16 bits.
+    ///
+    /// For the other arguments refer to `new`
+    pub fn with_prefixes(
+        base_address: usize,
+        operand_size_override: bool,
+        address_size_override: bool,
+        target: usize,
+    ) -> Self {
+        let displacement_width = if operand_size_override {
+            DisplacementWidth::Rel16
+        } else {
+            DisplacementWidth::Rel32
+        };
+        let address_mask = if operand_size_override {
+            u16::MAX as usize
+        } else if address_size_override {
+            u32::MAX as usize
+        } else {
+            usize::MAX
+        };
+        Self {
+            address_mask,
+            ..Self::new(base_address, displacement_width.bytes(), target)
+                .with_displacement_width(displacement_width)
+        }
+    }
+
+    /// Sets the width of the relative offset, which is read and sign-extended. Defaults to `DisplacementWidth::Rel32`.
+    ///
+    /// The instruction length is not adjusted, it still has to cover the bytes from the start of the relative offset
+    /// to the end of the instruction, e.g. 1 for `jmp rel8`.
+    pub fn with_displacement_width(mut self, displacement_width: DisplacementWidth) -> Self {
+        self.displacement_width = displacement_width.bytes();
+        self
+    }
+
+    /// The smallest and largest relative offset, which fits into the displacement
+    fn displacement_range(&self) -> (isize, isize) {
+        let bits = self.displacement_width * 8 - 1;
+        (-(1 << bits), (1 << bits) - 1)
+    }
+
+    /// Checks if the opcode before `offset` is one of the kinds passed to `with_opcodes`
+    fn is_accepted_opcode(&self, bytes: &[u8], offset: usize) -> bool {
+        self.mnemonics.is_none_or(|mnemonics| {
+            branch_mnemonic(bytes, offset)
+                .is_some_and(|mnemonic| mnemonics & 1 << mnemonic as u8 != 0)
+        })
+    }
+
+    /// Returns the smallest instruction length, under which the `offset` in `bytes` is a reference
+    pub fn match_at(&self, bytes: &[u8], offset: usize) -> Option<usize> {
+        if !fits(bytes, offset, self.displacement_width) {
+            return None;
+        }
+        if !self.is_accepted_opcode(bytes, offset) {
+            return None;
+        }
+        let value = D::read_displacement(bytes, offset, self.displacement_width);
+        self.instruction_lengths.iter().find(|&instruction_length| {
+            let address = resolve_relative(
+                self.base_address.wrapping_add(offset),
+                instruction_length,
+                value.wrapping_add(self.rip_adjust),
+            );
+            self.targets.contains(address & self.address_mask)
+        })
+    }
+
+    /// Adds `rip_adjust` to the address of the next instruction, before the relative offset is applied
+    ///
+    /// This is only required for unusual encodings, where the processor doesn't use `offset + instruction_length`
+    /// as the base of the relative offset. Defaults to 0.
+    pub fn with_rip_adjust(mut self, rip_adjust: isize) -> Self {
+        self.rip_adjust = rip_adjust;
+        self
+    }
+
+    /// Only accepts references, which are preceded by the opcode of a branch of one of the `kinds`
+    ///
+    /// This filters out displacements, which coincidentally lead to the target, e.g. when scanning code for calls.
+    /// Offsets, which are too close to the start of the bytes to be preceded by an opcode, never match.
+    /// Refer to `CodeHeuristicRelativeFinder` for the known opcodes.
+    pub fn with_opcodes(mut self, kinds: &[RefMnemonic]) -> Self {
+        self.mnemonics = Some(
+            kinds
+                .iter()
+                .fold(0, |mnemonics, &kind| mnemonics | 1 << kind as u8),
+        );
+        self
+    }
+
+    fn with_base(&self, base_address: usize) -> Self {
+        Self {
+            base_address,
+            ..*self
+        }
+    }
+
+    /// Finds the next reference, using `base_address` instead of the configured one for this call
+    pub fn next_with_base(&self, bytes: &[u8], base_address: usize) -> Option<usize> {
+        self.with_base(base_address).next(bytes)
+    }
+
+    /// Finds all references, using `base_address` instead of the configured one for this call
+    pub fn all_with_base<'a>(
+        &self,
+        bytes: &'a [u8],
+        base_address: usize,
+    ) -> impl Iterator<Item = usize> + 'a
+    where
+        D: 'a,
+    {
+        let finder = self.with_base(base_address);
+        (0..bytes.len()).filter(move |&i| finder.does_match(bytes, i))
+    }
+
+    /// Computes the lowest and highest address, which a relative offset in a buffer of `buffer_len` bytes can reach
+    ///
+    /// A relative offset can only reach `i32::MIN..=i32::MAX` (or the range of its displacement width) bytes around its instruction.
+    /// The addresses are clamped to the address space. Returns `None` if not a single relative offset fits into the buffer.
+    pub fn max_reach(&self, buffer_len: usize) -> Option<(usize, usize)> {
+        let (min, max) = self.unclamped_reach(buffer_len)?;
+        let clamp = |address: i128| address.clamp(0, usize::MAX as i128) as usize;
+        Some((clamp(min), clamp(max)))
+    }
+
+    /// Like `max_reach`, but without clamping, so the parts, which wrap around the address space, can be recovered
+    fn unclamped_reach(&self, buffer_len: usize) -> Option<(i128, i128)> {
+        let last_offset = buffer_len.checked_sub(self.displacement_width)?;
+        let shortest = self.instruction_lengths.iter().min()?;
+        let longest = self.instruction_lengths.iter().max()?;
+        let base = self.base_address as i128 + self.rip_adjust as i128;
+        let (lowest, highest) = self.displacement_range();
+        let min = base + shortest as i128 + lowest as i128;
+        let max = base + last_offset as i128 + longest as i128 + highest as i128;
+        Some((min, max))
+    }
+
+    /// Checks if any offset in a buffer of `buffer_len` bytes could possibly reference `target`
+    ///
+    /// A finder, whose target is out of `max_reach`, never matches,
+    /// unless the reach wraps around the address space and the target lies in the wrapped part.
+    pub fn is_satisfiable(&self, buffer_len: usize) -> bool {
+        let Some((min, max)) = self.unclamped_reach(buffer_len) else {
+            return false;
+        };
+        if self.address_mask != usize::MAX {
+            // Truncated addresses can end up anywhere below the mask
+            return self.targets.overlaps(0, self.address_mask);
         }
+        let address_space = usize::MAX as i128 + 1;
+        let clamp = |address: i128| address.clamp(0, usize::MAX as i128) as usize;
+        self.targets.overlaps(clamp(min), clamp(max))
+            || (min < 0
+                && self
+                    .targets
+                    .overlaps(clamp(min + address_space), usize::MAX))
+            || (max >= address_space && self.targets.overlaps(0, clamp(max - address_space)))
+    }
+
+    /// Finds all references and yields their addresses (`base_address + offset`) instead of their offsets
+    ///
+    /// References, whose address would leave the address space, are skipped
+    pub fn all_addresses<'a>(&'a self, bytes: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        self.all(bytes)
+            .filter_map(|offset| self.base_address.checked_add(offset))
+    }
+
+    /// Finds all references and pairs them with the instruction length, under which they reference the target
+    ///
+    /// Every offset is yielded once, with the shortest matching length. Refer to `match_at`
+    pub fn all_with_length<'a>(
+        &'a self,
+        bytes: &'a [u8],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        (0..bytes.len()).filter_map(move |i| self.match_at(bytes, i).map(|length| (i, length)))
+    }
+
+    /// Finds all references, whose preceding opcode is a branch of one of the `kinds`
+    ///
+    /// Refer to `CodeHeuristicRelativeFinder` for the known opcodes
+    pub fn all_by_opcode_kind<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        kinds: &'a [RefMnemonic],
+    ) -> impl Iterator<Item = usize> + 'a {
+        self.all(bytes).filter(move |&i| {
+            branch_mnemonic(bytes, i).is_some_and(|mnemonic| kinds.contains(&mnemonic))
+        })
+    }
+
+    /// Finds all relative offsets, which would lead the processor from their own location to `target`
+    ///
+    /// This is the same as `all`, the name only clarifies the direction
+    pub fn sources_of<'a>(&'a self, bytes: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        self.all(bytes)
+    }
+
+    /// Finds all relative offsets, which would lead the processor from `target` to their own location
+    ///
+    /// This treats `target` as the location of the instruction and the scanned offset as its destination,
+    /// meaning `target + instruction_length + displacement` has to equal `base_address + offset`.
+    /// For a finder created with `new_range`, the instruction is located at the start of the range.
+    /// An empty range never matches. Like `sources_of`, this respects `with_opcodes`.
+    pub fn destinations_from<'a>(&'a self, bytes: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        let end = if self.targets.is_empty() {
+            0
+        } else {
+            bytes.len().saturating_sub(self.displacement_width - 1)
+        };
+        (0..end).filter(move |&i| {
+            if !self.is_accepted_opcode(bytes, i) {
+                return false;
+            }
+            let value = D::read_displacement(bytes, i, self.displacement_width);
+            self.instruction_lengths.iter().any(|instruction_length| {
+                is_relative_match(
+                    self.targets.lowest(),
+                    instruction_length,
+                    value.wrapping_add(self.rip_adjust),
+                    self.base_address.wrapping_add(i),
+                )
+            })
+        })
     }
 }
 
-impl<Endian: ByteOrder> XRefFinder for RelativeFinder<Endian> {
+impl<D: Decoder> XRefFinder for RelativeFinder<D> {
     fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
-        let i32_size = size_of::<i32>();
-        if bytes.len() - offset < i32_size {
-            return false;
+        self.match_at(bytes, offset).is_some()
+    }
+
+    fn match_width(&self) -> usize {
+        self.displacement_width
+    }
+
+    fn lookbehind(&self) -> usize {
+        if self.mnemonics.is_some() {
+            BRANCH_OPCODE_LENGTH
+        } else {
+            0
         }
-        does_match_relative::<Endian>(
-            bytes,
-            offset,
-            self.base_address,
-            self.instruction_length,
-            self.target,
-        )
     }
 }
@@ -0,0 +1,91 @@
+use alloc::{boxed::Box, vec::Vec};
+use byteorder::ByteOrder;
+use core::marker::PhantomData;
+
+use crate::{AbsoluteFinder, MaskedFinder, RelativeFinder, XRefFinder};
+
+type LabeledFinder<'a> = (Box<dyn XRefFinder + 'a>, Option<&'a str>);
+
+/// Assembles a `Scanner` out of several finders
+pub struct ScanBuilder<'a, Endian: ByteOrder> {
+    finders: Vec<LabeledFinder<'a>>,
+    endian: PhantomData<Endian>,
+}
+
+impl<'a, Endian: ByteOrder + 'a> ScanBuilder<'a, Endian> {
+    /// Creates a new ScanBuilder without any finders
+    pub fn new() -> Self {
+        Self {
+            finders: Vec::new(),
+            endian: PhantomData,
+        }
+    }
+
+    /// Adds an arbitrary finder
+    pub fn finder(mut self, finder: impl XRefFinder + 'a) -> Self {
+        self.finders.push((Box::new(finder), None));
+        self
+    }
+
+    /// Adds an `AbsoluteFinder`
+    pub fn absolute(self, target: usize) -> Self {
+        self.finder(AbsoluteFinder::<Endian>::new(target))
+    }
+
+    /// Adds a `RelativeFinder`
+    pub fn relative(self, base_address: usize, instruction_length: usize, target: usize) -> Self {
+        self.finder(RelativeFinder::<Endian>::new(
+            base_address,
+            instruction_length,
+            target,
+        ))
+    }
+
+    /// Adds a `MaskedFinder`
+    pub fn masked(self, mask: usize, target: usize) -> Self {
+        self.finder(MaskedFinder::<Endian>::new(mask, target))
+    }
+
+    /// Labels the finder, which was added last
+    pub fn labeled(mut self, label: &'a str) -> Self {
+        if let Some((_, last)) = self.finders.last_mut() {
+            *last = Some(label);
+        }
+        self
+    }
+
+    /// Creates the `Scanner` running all added finders
+    pub fn build(self) -> Scanner<'a> {
+        Scanner {
+            finders: self.finders,
+        }
+    }
+}
+
+impl<'a, Endian: ByteOrder + 'a> Default for ScanBuilder<'a, Endian> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Runs several finders over a buffer at once, refer to `ScanBuilder`
+pub struct Scanner<'a> {
+    finders: Vec<LabeledFinder<'a>>,
+}
+
+impl<'a> Scanner<'a> {
+    /// Finds all references of all finders
+    ///
+    /// Yields the offset and the label of the finder, ordered by offset and then by the order, in which the finders were added.
+    pub fn scan<'s>(
+        &'s self,
+        bytes: &'s [u8],
+    ) -> impl Iterator<Item = (usize, Option<&'a str>)> + 's {
+        (0..bytes.len()).flat_map(move |i| {
+            self.finders
+                .iter()
+                .filter(move |(finder, _)| finder.does_match(bytes, i))
+                .map(move |&(_, label)| (i, label))
+        })
+    }
+}
@@ -0,0 +1,106 @@
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+
+use crate::{
+    matches::resume_iter, AbsoluteFinder, EndianFinder, Endianness, PointerWidth, RelativeFinder,
+    XRefFinder,
+};
+
+/// A section of an executable, as it is loaded into memory
+///
+/// This is the information, which object file parsers (e.g. the `object` crate) provide for every section.
+#[derive(Debug, Clone, Copy)]
+pub struct Section<'a> {
+    /// The index of the section in its file, which is reported with every reference
+    pub index: usize,
+    /// The address, at which the section is loaded
+    pub address: usize,
+    /// The contents of the section
+    pub data: &'a [u8],
+}
+
+/// Matches the relative and the absolute references of a section, like a `RelativeAndAbsoluteFinder` of any pointer width
+#[derive(Debug, Clone, Copy)]
+struct SectionFinder<Endian: ByteOrder> {
+    relative: RelativeFinder<Endian>,
+    absolute: AbsoluteFinder<Endian>,
+}
+
+impl<Endian: ByteOrder> SectionFinder<Endian> {
+    fn new(
+        section: &Section,
+        instruction_length: usize,
+        pointer_width: PointerWidth,
+        target: usize,
+    ) -> Self {
+        Self {
+            relative: RelativeFinder::new(section.address, instruction_length, target),
+            absolute: AbsoluteFinder::new(target).with_pointer_width(pointer_width),
+        }
+    }
+}
+
+impl<Endian: ByteOrder> XRefFinder for SectionFinder<Endian> {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        self.relative.does_match(bytes, offset) || self.absolute.does_match(bytes, offset)
+    }
+
+    fn match_width(&self) -> usize {
+        self.relative.match_width().max(self.absolute.match_width())
+    }
+
+    fn min_bytes(&self) -> usize {
+        self.relative.min_bytes().min(self.absolute.min_bytes())
+    }
+
+    fn next_from(&self, bytes: &[u8], start: usize) -> Option<usize> {
+        // Both finders keep their faster implementations
+        self.relative
+            .next_from(bytes, start)
+            .into_iter()
+            .chain(self.absolute.next_from(bytes, start))
+            .min()
+    }
+}
+
+/// Finds the relative and absolute references to `target` in every one of the `sections`
+///
+/// Every section gets its own `RelativeFinder`, whose base address is the address of the section,
+/// and an `AbsoluteFinder` of the `pointer_width`.
+/// Yields the index of the section and the address of every reference.
+/// References, whose address would leave the address space, are skipped.
+///
+/// Arguments:
+///
+/// * `sections`: The sections to scan, e.g. the executable ones
+/// * `endianness`: The byte order of the file
+/// * `instruction_length`: Refer to `RelativeFinder::new`
+/// * `pointer_width`: The width of absolute references, refer to `AbsoluteFinder::with_pointer_width`
+/// * `target`: The address, which the references should point to
+pub fn scan_sections<'a>(
+    sections: impl IntoIterator<Item = Section<'a>> + 'a,
+    endianness: Endianness,
+    instruction_length: usize,
+    pointer_width: PointerWidth,
+    target: usize,
+) -> impl Iterator<Item = (usize, usize)> + 'a {
+    sections.into_iter().flat_map(move |section| {
+        let finder: EndianFinder<SectionFinder<LittleEndian>, SectionFinder<BigEndian>> =
+            match endianness {
+                Endianness::Little => EndianFinder::Little(SectionFinder::new(
+                    &section,
+                    instruction_length,
+                    pointer_width,
+                    target,
+                )),
+                Endianness::Big => EndianFinder::Big(SectionFinder::new(
+                    &section,
+                    instruction_length,
+                    pointer_width,
+                    target,
+                )),
+            };
+        resume_iter(finder, section.data)
+            .filter_map(move |offset| section.address.checked_add(offset))
+            .map(move |address| (section.index, address))
+    })
+}
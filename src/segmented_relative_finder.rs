@@ -0,0 +1,63 @@
+use core::{marker::PhantomData, mem::size_of, ops::Range};
+
+use crate::{read::fits, relative_finder::does_match_relative, Decoder, XRefFinder};
+
+#[derive(Debug, Clone, Copy)]
+pub struct SegmentedRelativeFinder<'a, D: Decoder> {
+    segments: &'a [(Range<usize>, usize)],
+    instruction_length: usize,
+    target: usize,
+    decoder: PhantomData<D>,
+}
+
+impl<'a, D: Decoder> SegmentedRelativeFinder<'a, D> {
+    /// Creates a new SegmentedRelativeFinder, that can then find relative cross references in a buffer spanning several modules
+    ///
+    /// Arguments:
+    ///
+    /// * `segments`: Pairs of `(range, base_address)`, where `range` is the part of the buffer occupied by a module
+    ///   and `base_address` is the address, at which `range.start` is located. Offsets outside of all ranges never match.
+    /// * `instruction_length`, `target`: Refer to `RelativeFinder`
+    pub fn new(
+        segments: &'a [(Range<usize>, usize)],
+        instruction_length: usize,
+        target: usize,
+    ) -> Self {
+        Self {
+            segments,
+            instruction_length,
+            target,
+            decoder: PhantomData,
+        }
+    }
+
+    /// Returns the base address of the buffer, as seen from the module containing `offset`
+    fn base_address(&self, offset: usize) -> Option<usize> {
+        self.segments
+            .iter()
+            .find(|(range, _)| range.contains(&offset))
+            .map(|(range, base_address)| base_address.wrapping_sub(range.start))
+    }
+}
+
+impl<D: Decoder> XRefFinder for SegmentedRelativeFinder<'_, D> {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        if !fits(bytes, offset, size_of::<i32>()) {
+            return false;
+        }
+        self.base_address(offset).is_some_and(|base_address| {
+            does_match_relative::<D>(
+                bytes,
+                offset,
+                base_address,
+                self.instruction_length,
+                0,
+                self.target,
+            )
+        })
+    }
+
+    fn match_width(&self) -> usize {
+        size_of::<i32>()
+    }
+}
@@ -0,0 +1,49 @@
+use byteorder::ByteOrder;
+use core::{marker::PhantomData, mem::size_of};
+
+use crate::{
+    absolute_finder::is_absolute_match,
+    read::{fits, read_addr},
+    XRefFinder,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct SelfPointerFinder<Endian: ByteOrder> {
+    image_base: usize,
+    endian: PhantomData<Endian>,
+}
+
+impl<Endian: ByteOrder> SelfPointerFinder<Endian> {
+    /// Creates a new SelfPointerFinder, that can then find absolute pointers, which point to their own location
+    ///
+    /// Arguments:
+    ///
+    /// * `image_base`: The address, at which the scanned buffer is located
+    pub fn new(image_base: usize) -> Self {
+        Self {
+            image_base,
+            endian: PhantomData,
+        }
+    }
+
+    /// Returns the pointer stored at `offset`, if it points to `offset`
+    pub fn match_at(&self, bytes: &[u8], offset: usize) -> Option<usize> {
+        if !fits(bytes, offset, size_of::<usize>()) {
+            return None;
+        }
+        let value = read_addr::<Endian>(bytes, offset, size_of::<usize>());
+        self.image_base
+            .checked_add(offset)
+            .filter(|&address| is_absolute_match(value, address))
+    }
+}
+
+impl<Endian: ByteOrder> XRefFinder for SelfPointerFinder<Endian> {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        self.match_at(bytes, offset).is_some()
+    }
+
+    fn match_width(&self) -> usize {
+        size_of::<usize>()
+    }
+}
@@ -0,0 +1,62 @@
+use byteorder::ByteOrder;
+use core::{marker::PhantomData, mem::size_of};
+
+use crate::{
+    read::{fits, read_disp},
+    XRefFinder,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct SibFinder<Endian: ByteOrder> {
+    base_value: usize,
+    index_value: usize,
+    scale: usize,
+    target: usize,
+    endian: PhantomData<Endian>,
+}
+
+impl<Endian: ByteOrder> SibFinder<Endian> {
+    /// Creates a new SibFinder, that can then find displacements of `[base + index * scale + disp]` operands
+    ///
+    /// Arguments:
+    ///
+    /// * `base_value`: The value of the base register
+    /// * `index_value`: The value of the index register
+    /// * `scale`: The factor of the index register, one of 1, 2, 4 or 8
+    /// * `target`: The address, which the reference should point to
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` isn't one of 1, 2, 4 or 8
+    pub fn new(base_value: usize, index_value: usize, scale: usize, target: usize) -> Self {
+        assert!(
+            matches!(scale, 1 | 2 | 4 | 8),
+            "scale has to be one of 1, 2, 4 or 8"
+        );
+        Self {
+            base_value,
+            index_value,
+            scale,
+            target,
+            endian: PhantomData,
+        }
+    }
+}
+
+impl<Endian: ByteOrder> XRefFinder for SibFinder<Endian> {
+    fn does_match(&self, bytes: &[u8], offset: usize) -> bool {
+        if !fits(bytes, offset, size_of::<i32>()) {
+            return false;
+        }
+        let value = read_disp::<Endian>(bytes, offset, size_of::<i32>());
+        let address = self
+            .base_value
+            .wrapping_add(self.index_value.wrapping_mul(self.scale))
+            .wrapping_add_signed(value);
+        address == self.target
+    }
+
+    fn match_width(&self) -> usize {
+        size_of::<i32>()
+    }
+}
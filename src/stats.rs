@@ -0,0 +1,24 @@
+/// Summary of all references found in a buffer, refer to `XRefFinder::stats`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MatchStats {
+    /// The amount of references
+    pub count: usize,
+    /// The offset of the first reference
+    pub first: Option<usize>,
+    /// The offset of the last reference
+    pub last: Option<usize>,
+    /// The average distance between two consecutive references, this requires at least two references
+    pub mean_gap: Option<f64>,
+}
+
+impl MatchStats {
+    pub(crate) fn push(mut self, offset: usize) -> Self {
+        self.count += 1;
+        self.first.get_or_insert(offset);
+        self.last = Some(offset);
+        if let (Some(first), true) = (self.first, self.count > 1) {
+            self.mean_gap = Some((offset - first) as f64 / (self.count - 1) as f64);
+        }
+        self
+    }
+}
@@ -0,0 +1,87 @@
+use alloc::vec::Vec;
+
+use crate::{Matches, XRefFinder};
+
+/// Finds references in a stream of chunks, e.g. process memory, which is read piece by piece
+///
+/// The last `match_width - 1` bytes of every chunk are kept and prepended to the next chunk,
+/// so references, which span two chunks, are still found. The `lookbehind` bytes before them are kept as well,
+/// so finders inspecting the preceding opcode still see it, and so are the `lookahead` bytes after a reference,
+/// which finders inspect to reject it. Offsets are counted from the start of the stream.
+pub struct StreamingFinder<B> {
+    finder_at: B,
+    base_address: usize,
+    width: usize,
+    lookbehind: usize,
+    lookahead: usize,
+    /// The bytes, which haven't been fully scanned yet, preceded by up to `lookbehind` scanned bytes
+    buffer: Vec<u8>,
+    /// The offset of the first byte of `buffer` in the stream
+    position: usize,
+    /// The index of the first byte in `buffer`, that hasn't been scanned yet
+    unscanned: usize,
+}
+
+impl<F: XRefFinder, B: Fn(usize) -> F> StreamingFinder<B> {
+    /// Creates a new StreamingFinder, that can then find references in a stream of chunks
+    ///
+    /// Finders, like `RelativeFinder`, depend on the address of the scanned bytes, which changes with every chunk.
+    /// So instead of a finder, this takes a function creating the finder for bytes located at an address.
+    ///
+    /// Arguments:
+    ///
+    /// * `base_address`: The address, at which the first byte of the stream is located
+    /// * `finder_at`: Creates the finder for bytes located at the passed address
+    pub fn new(base_address: usize, finder_at: B) -> Self {
+        let finder = finder_at(base_address);
+        Self {
+            width: finder.match_width(),
+            lookbehind: finder.lookbehind(),
+            lookahead: finder.lookahead(),
+            finder_at,
+            base_address,
+            buffer: Vec::new(),
+            position: 0,
+            unscanned: 0,
+        }
+    }
+
+    /// Scans the next chunk and returns the offsets of the found references in the stream
+    ///
+    /// References, which start in the last `match_width - 1 + lookahead` bytes, are only reported once the next chunk or `finish` completes them.
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<usize> {
+        self.buffer.extend_from_slice(chunk);
+        let end = self
+            .buffer
+            .len()
+            .saturating_sub(self.width.saturating_sub(1) + self.lookahead)
+            .max(self.unscanned);
+        let matches = self.scan(end);
+        let dropped = end.saturating_sub(self.lookbehind);
+        self.buffer.drain(..dropped);
+        self.position += dropped;
+        self.unscanned = end - dropped;
+        matches
+    }
+
+    /// Scans the bytes, which are left at the end of the stream, and returns the offsets of the found references in the stream
+    ///
+    /// The StreamingFinder starts a new stream directly behind the old one afterwards.
+    pub fn finish(&mut self) -> Vec<usize> {
+        let matches = self.scan(self.buffer.len());
+        self.position += self.buffer.len();
+        self.buffer.clear();
+        self.unscanned = 0;
+        matches
+    }
+
+    /// Finds the references in `buffer`, which start in `unscanned..end`
+    fn scan(&self, end: usize) -> Vec<usize> {
+        let finder = (self.finder_at)(self.base_address.wrapping_add(self.position));
+        Matches::new(&finder, &self.buffer[..])
+            .skip_while(|&i| i < self.unscanned)
+            .take_while(|&i| i < end)
+            .map(|i| self.position + i)
+            .collect()
+    }
+}
@@ -0,0 +1,71 @@
+/// The addresses, which a finder accepts as its target
+///
+/// Empty if `lowest > highest`
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TargetRange {
+    lowest: usize,
+    highest: usize,
+}
+
+impl TargetRange {
+    pub(crate) const fn exact(target: usize) -> Self {
+        Self {
+            lowest: target,
+            highest: target,
+        }
+    }
+
+    /// Accepts every address in `lo..hi`, nothing if `lo >= hi`
+    pub(crate) const fn new(lo: usize, hi: usize) -> Self {
+        if lo >= hi {
+            Self {
+                lowest: 1,
+                highest: 0,
+            }
+        } else {
+            Self {
+                lowest: lo,
+                highest: hi - 1,
+            }
+        }
+    }
+
+    pub(crate) const fn is_empty(&self) -> bool {
+        self.lowest > self.highest
+    }
+
+    pub(crate) const fn contains(&self, address: usize) -> bool {
+        self.lowest <= address && address <= self.highest
+    }
+
+    /// Checks if any address in `min..=max` is accepted
+    pub(crate) const fn overlaps(&self, min: usize, max: usize) -> bool {
+        !self.is_empty() && self.lowest <= max && min <= self.highest
+    }
+
+    /// Drops every address above `max`
+    pub(crate) const fn capped(self, max: usize) -> Self {
+        Self {
+            lowest: self.lowest,
+            highest: if self.highest < max {
+                self.highest
+            } else {
+                max
+            },
+        }
+    }
+
+    /// The lowest accepted address
+    pub(crate) const fn lowest(&self) -> usize {
+        self.lowest
+    }
+
+    /// Returns the target, if exactly one address is accepted
+    pub(crate) const fn single(&self) -> Option<usize> {
+        if self.lowest == self.highest {
+            Some(self.lowest)
+        } else {
+            None
+        }
+    }
+}